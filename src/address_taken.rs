@@ -0,0 +1,155 @@
+use crate::operands::{constant_operands, instruction_operands, terminator_operands};
+use crate::ModuleFunctions;
+use either::Either;
+use llvm_ir::{Constant, ConstantRef, Instruction, Operand, Terminator};
+use std::collections::HashSet;
+
+/// The set of functions in the analyzed `Module`(s) whose address is taken,
+/// i.e., which are referenced anywhere other than as the function being called
+/// by a direct call.
+///
+/// Only these functions may be the target of a call through a function
+/// pointer (assuming that function pointers are not conjured from integers or
+/// from other modules).
+///
+/// References are found in instruction operands (including call arguments),
+/// global variable initializers, aliases, and personality functions, looking
+/// through constant expressions such as bitcasts.
+///
+/// To construct an `AddressTakenFunctions`, use
+/// [`ModuleAnalysis`](struct.ModuleAnalysis.html) or
+/// [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
+pub struct AddressTakenFunctions<'m> {
+    functions: HashSet<&'m str>,
+}
+
+impl<'m> AddressTakenFunctions<'m> {
+    pub(crate) fn new<'a>(modules: impl IntoIterator<Item = ModuleFunctions<'m, 'a>>) -> Self
+    where
+        'm: 'a,
+    {
+        let mut functions = HashSet::new();
+        for module_functions in modules {
+            let module = module_functions.module;
+            let function_names: HashSet<&'m str> = module_functions
+                .functions
+                .iter()
+                .map(|f| f.name.as_str())
+                .chain(
+                    module
+                        .func_declarations
+                        .iter()
+                        .map(|decl| decl.name.as_str()),
+                )
+                .collect();
+            let mut add_constant = |constant: &'m ConstantRef| {
+                add_referenced_functions(constant, &function_names, &mut functions)
+            };
+            for f in module_functions.functions {
+                for bb in &f.basic_blocks {
+                    for inst in &bb.instrs {
+                        let operands = match inst {
+                            Instruction::Call(call) => operands_except_direct_callee(
+                                &call.function,
+                                instruction_operands(inst),
+                            ),
+                            _ => instruction_operands(inst),
+                        };
+                        for operand in operands {
+                            if let Operand::ConstantOperand(constant) = operand {
+                                add_constant(constant);
+                            }
+                        }
+                    }
+                    let operands = match &bb.term {
+                        Terminator::Invoke(invoke) => operands_except_direct_callee(
+                            &invoke.function,
+                            terminator_operands(&bb.term),
+                        ),
+                        #[cfg(not(feature = "llvm-8"))]
+                        Terminator::CallBr(callbr) => operands_except_direct_callee(
+                            &callbr.function,
+                            terminator_operands(&bb.term),
+                        ),
+                        term => terminator_operands(term),
+                    };
+                    for operand in operands {
+                        if let Operand::ConstantOperand(constant) = operand {
+                            add_constant(constant);
+                        }
+                    }
+                }
+                if let Some(personality) = &f.personality_function {
+                    add_constant(personality);
+                }
+            }
+            for var in &module.global_vars {
+                if let Some(initializer) = &var.initializer {
+                    add_constant(initializer);
+                }
+            }
+            for alias in &module.global_aliases {
+                add_constant(&alias.aliasee);
+            }
+        }
+        Self { functions }
+    }
+
+    /// Is the address of the function with the given name taken?
+    pub fn is_address_taken(&self, func_name: &str) -> bool {
+        self.functions.contains(func_name)
+    }
+
+    /// Iterate over the names of all the functions whose address is taken
+    pub fn functions<'s>(&'s self) -> impl Iterator<Item = &'m str> + 's {
+        self.functions.iter().copied()
+    }
+}
+
+/// Is the called function a direct reference to a function (possibly through
+/// a bitcast)? Such a reference doesn't take the function's address.
+fn is_direct_callee<T>(function: &Either<T, Operand>) -> bool {
+    match function {
+        Either::Right(Operand::ConstantOperand(cref)) => match cref.as_ref() {
+            Constant::GlobalReference { .. } => true,
+            Constant::BitCast(bitcast) => {
+                matches!(bitcast.operand.as_ref(), Constant::GlobalReference { .. })
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Given the operands of a call (which begin with the called function, unless
+/// it is inline assembly), drop the called function if it is direct
+fn operands_except_direct_callee<'a, T>(
+    function: &Either<T, Operand>,
+    operands: Vec<&'a Operand>,
+) -> Vec<&'a Operand> {
+    if is_direct_callee(function) {
+        operands.into_iter().skip(1).collect()
+    } else {
+        operands
+    }
+}
+
+/// Add to `functions` all the functions in `function_names` which are
+/// referenced in the given constant, looking through constant expressions
+fn add_referenced_functions<'m>(
+    constant: &'m ConstantRef,
+    function_names: &HashSet<&'m str>,
+    functions: &mut HashSet<&'m str>,
+) {
+    let mut worklist = vec![constant];
+    while let Some(constant) = worklist.pop() {
+        match constant.as_ref() {
+            Constant::GlobalReference { name, .. } => {
+                if function_names.contains(name.as_str()) {
+                    functions.insert(name);
+                }
+            }
+            constant => worklist.extend(constant_operands(constant)),
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{FunctionAnalysis, ModuleAnalysis};
+use once_cell::sync::OnceCell;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// A custom analysis of a single function, which can be lazily computed and
+/// cached by a [`FunctionAnalysis`](struct.FunctionAnalysis.html) in the same
+/// way as the built-in analyses, using `FunctionAnalysis::analysis()`.
+///
+/// Custom analyses are identified by their type, so each type implementing
+/// this trait is computed at most once per function.
+///
+/// `compute()` can use any of the built-in analyses of the function (e.g.,
+/// `analysis.dominator_tree()`), or other custom analyses (with
+/// `analysis.analysis::<OtherAnalysis>()`); these are its dependencies, and
+/// are themselves computed and cached as needed. Custom analyses must not
+/// depend on themselves, directly or indirectly.
+pub trait FunctionAnalysisPass: Any + Send + Sync {
+    /// Compute the analysis for the function analyzed by `analysis`
+    fn compute(analysis: &FunctionAnalysis) -> Self
+    where
+        Self: Sized;
+}
+
+/// A custom analysis of a `Module`, which can be lazily computed and cached by
+/// a [`ModuleAnalysis`](struct.ModuleAnalysis.html) in the same way as the
+/// built-in analyses, using `ModuleAnalysis::analysis()`.
+///
+/// Like a [`FunctionAnalysisPass`](trait.FunctionAnalysisPass.html), but
+/// `compute()` can also use the analyses of each function in the `Module`,
+/// via `analysis.fn_analysis()`. To see functions replaced or added with
+/// `ModuleAnalysis::replace_function()` or `ModuleAnalysis::add_function()`,
+/// iterate over `analysis.functions()` rather than
+/// `analysis.module().functions`.
+pub trait ModuleAnalysisPass: Any + Send + Sync {
+    /// Compute the analysis for the `Module` analyzed by `analysis`
+    fn compute(analysis: &ModuleAnalysis) -> Self
+    where
+        Self: Sized;
+}
+
+/// The (eventual) result of a custom analysis, with its type erased
+type ResultSlot = Arc<OnceCell<Arc<dyn Any + Send + Sync>>>;
+
+/// Caches the results of custom analyses, keyed by their type
+#[derive(Default)]
+pub(crate) struct AnalysisManager {
+    /// Map from the type of each custom analysis to its result, if computed.
+    ///
+    /// The lock is held only while looking up (or inserting) an entry, not
+    /// while computing an analysis, so that analyses can request their
+    /// dependencies
+    results: Mutex<HashMap<TypeId, ResultSlot>>,
+}
+
+impl AnalysisManager {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Discard all the cached results
+    pub(crate) fn clear(&mut self) {
+        self.results
+            .get_mut()
+            .expect("analysis cache lock should not be poisoned")
+            .clear();
+    }
+
+    /// Get the cached result of the analysis `A`, or if it isn't cached,
+    /// compute it using the given closure, then cache that result and return
+    /// it
+    pub(crate) fn get_or_insert_with<A: Any + Send + Sync>(
+        &self,
+        compute: impl FnOnce() -> A,
+    ) -> Arc<A> {
+        let cell = Arc::clone(
+            self.results
+                .lock()
+                .expect("analysis cache lock should not be poisoned")
+                .entry(TypeId::of::<A>())
+                .or_default(),
+        );
+        let result = cell.get_or_init(|| Arc::new(compute()));
+        Arc::clone(result)
+            .downcast()
+            .unwrap_or_else(|_| panic!("cached analysis should have the type it is keyed by"))
+    }
+}
@@ -1,12 +1,21 @@
-use crate::error::{self, Result};
+use crate::address_taken::AddressTakenFunctions;
+use crate::dot::{dot_to_string, escape, write_digraph, DotOptions};
+use crate::error::{Error, Result};
 use crate::functions_by_type::FunctionsByType;
+use crate::ModuleFunctions;
 use either::Either;
+#[cfg(not(feature = "llvm-8"))]
+use llvm_ir::terminator::CallBr;
 use llvm_ir::{
     instruction::{Call, InlineAssembly},
     terminator::Invoke,
-    Constant, Instruction, Module, Operand, Terminator, TypeRef,
+    Constant, Instruction, Module, Name, Operand, Terminator, TypeRef,
 };
+use petgraph::algo::tarjan_scc;
 use petgraph::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::io::{self, Write};
 
 /// The call graph for the analyzed `Module`(s): which functions may call which
 /// other functions.
@@ -15,105 +24,254 @@ use petgraph::prelude::*;
 /// or [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
 pub struct CallGraph<'m> {
     /// the call graph itself. Nodes are function names, and an edge from F to G
-    /// indicates F may call G
-    graph: DiGraphMap<&'m str, ()>,
+    /// indicates F may call G. Each edge is labeled with the `CallSite`s in F
+    /// which may call G.
+    graph: DiGraphMap<&'m str, Vec<CallSite<'m>>>,
+    /// The strongly connected components of the call graph, in bottom-up
+    /// order: every SCC comes after all the SCCs containing functions it may
+    /// call. The functions in each SCC are sorted by name.
+    sccs: Vec<Vec<&'m str>>,
+    /// Map from function name to the index of its SCC in `sccs`
+    scc_index: HashMap<&'m str, usize>,
+}
+
+/// A `CallSite` is a particular call instruction (or `Invoke` or `CallBr`
+/// terminator), which may call a particular function.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct CallSite<'m> {
+    /// Name of the function containing the call
+    pub caller: &'m str,
+    /// Name of the basic block containing the call
+    pub block: &'m Name,
+    /// Index of the call in the block's instructions. For `Invoke` and
+    /// `CallBr`, which are terminators, this is the number of instructions in
+    /// the block, as the terminator comes after all of them.
+    pub index: usize,
+    /// Which kind of instruction makes the call
+    pub inst_kind: CallInstKind,
+    /// How we determined that the call may go to this particular callee
+    pub edge_kind: CallEdgeKind,
+}
+
+/// The kind of instruction at a `CallSite`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum CallInstKind {
+    /// A `Call` instruction
+    Call,
+    /// An `Invoke` terminator
+    Invoke,
+    /// A `CallBr` terminator
+    CallBr,
+}
+
+/// How a `CallSite` was determined to (possibly) call a particular function
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum CallEdgeKind {
+    /// The call is directly to the function
+    Direct,
+    /// The call is directly to the function, bitcast to a different type
+    BitcastDirect,
+    /// The call is through a function pointer, which we conservatively assume
+    /// may point to any function in the analyzed `Module`(s) with the
+    /// appropriate type (and, depending on the `IndirectCallTargets`, whose
+    /// address is taken). For a `CallBr` with LLVM 15 or later, whose function
+    /// type isn't available, it may point to a function of any type.
+    FunctionPointer,
+}
+
+/// Which functions a call through a function pointer is assumed to possibly
+/// call, when building a `CallGraph`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum IndirectCallTargets {
+    /// Any function in the analyzed `Module`(s) which has the appropriate type
+    /// and whose address is taken (see `AddressTakenFunctions`). This is what
+    /// `ModuleAnalysis::call_graph()` and `CrossModuleAnalysis::call_graph()`
+    /// use.
+    AddressTaken,
+    /// Any function in the analyzed `Module`(s) which has the appropriate type,
+    /// whether or not its address is taken. This is more conservative, e.g. if
+    /// function pointers may be computed in ways the address-taken analysis
+    /// can't see, such as from integers.
+    MatchingType,
+}
+
+impl fmt::Display for CallEdgeKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CallEdgeKind::Direct => write!(f, "direct"),
+            CallEdgeKind::BitcastDirect => write!(f, "bitcast"),
+            CallEdgeKind::FunctionPointer => write!(f, "function pointer"),
+        }
+    }
 }
 
 impl<'m> CallGraph<'m> {
-    pub(crate) fn new(
-        modules: impl IntoIterator<Item = &'m Module>,
+    pub(crate) fn new<'a>(
+        modules: impl IntoIterator<Item = ModuleFunctions<'m, 'a>>,
         functions_by_type: &FunctionsByType<'m>,
-    ) -> Self {
-        let mut graph: DiGraphMap<&'m str, ()> = DiGraphMap::new();
+        address_taken: Option<&AddressTakenFunctions<'m>>,
+    ) -> Self
+    where
+        'm: 'a,
+    {
+        let mut graph: DiGraphMap<&'m str, Vec<CallSite<'m>>> = DiGraphMap::new();
+
+        // If `address_taken` is `None`, we don't filter by it. If we don't
+        // know the type of the callee, we conservatively assume the call may
+        // go to a function of any type.
+        let functions_with_callee_ty = |call: &CallOrInvoke<'m>| -> Vec<&'m str> {
+            let candidates = match call.callee_ty() {
+                Some(ty) => Either::Left(functions_by_type.functions_with_type(&ty)),
+                None => Either::Right(functions_by_type.functions()),
+            };
+            candidates
+                .filter(|func_name| match address_taken {
+                    Some(address_taken) => address_taken.is_address_taken(func_name),
+                    None => true,
+                })
+                .collect()
+        };
 
-        let add_edge_for_call = |graph: &mut DiGraphMap<_, _>,
-                                 caller: &'m str,
-                                 call: CallOrInvoke<'m>| {
+        // Get the functions the call may go to, and how we determined them
+        let targets_of_call = |call: &CallOrInvoke<'m>| -> (CallEdgeKind, Vec<&'m str>) {
             match call.callee() {
-                Either::Right(Operand::ConstantOperand(cref)) => {
-                    match cref.as_ref() {
-                        Constant::GlobalReference { name, .. } => {
-                            graph.add_edge(caller, name, ());
-                        }
-                        Constant::BitCast(llvm_ir::constant::BitCast { operand, .. }) => {
-                            match operand.as_ref() {
-                                Constant::GlobalReference { name, .. } => {
-                                    graph.add_edge(caller, name, ());
-                                }
-                                _ => {
-                                    for target in
-                                        functions_by_type.functions_with_type(&call.callee_ty())
-                                    {
-                                        graph.add_edge(caller, target, ());
-                                    }
-                                }
-                            }
-                        }
-                        _ => {
-                            // a constant function pointer.
-                            // Assume that this function pointer could point
-                            // to any function in the current module that has
-                            // the appropriate type
-                            for target in functions_by_type.functions_with_type(&call.callee_ty()) {
-                                graph.add_edge(caller, target, ());
+                Either::Right(Operand::ConstantOperand(cref)) => match cref.as_ref() {
+                    Constant::GlobalReference { name, .. } => {
+                        (CallEdgeKind::Direct, vec![name.as_str()])
+                    }
+                    Constant::BitCast(llvm_ir::constant::BitCast { operand, .. }) => {
+                        match operand.as_ref() {
+                            Constant::GlobalReference { name, .. } => {
+                                (CallEdgeKind::BitcastDirect, vec![name.as_str()])
                             }
+                            _ => (
+                                CallEdgeKind::FunctionPointer,
+                                functions_with_callee_ty(call),
+                            ),
                         }
                     }
-                }
+                    _ => {
+                        // a constant function pointer.
+                        // Assume that this function pointer could point
+                        // to any (address-taken) function in the current
+                        // module that has the appropriate type
+                        (
+                            CallEdgeKind::FunctionPointer,
+                            functions_with_callee_ty(call),
+                        )
+                    }
+                },
                 Either::Right(_) => {
                     // Assume that this function pointer could point to any
-                    // function in the current module that has the
-                    // appropriate type
-                    for target in functions_by_type.functions_with_type(&call.callee_ty()) {
-                        graph.add_edge(caller, target, ());
+                    // (address-taken) function in the current module that
+                    // has the appropriate type
+                    (
+                        CallEdgeKind::FunctionPointer,
+                        functions_with_callee_ty(call),
+                    )
+                }
+                Either::Left(_) => {
+                    // ignore calls to inline assembly
+                    (CallEdgeKind::Direct, vec![])
+                }
+            }
+        };
+
+        let add_edges_for_call = |graph: &mut DiGraphMap<&'m str, Vec<CallSite<'m>>>,
+                                  caller: &'m str,
+                                  block: &'m Name,
+                                  index: usize,
+                                  call: CallOrInvoke<'m>| {
+            let (edge_kind, targets) = targets_of_call(&call);
+            let site = CallSite {
+                caller,
+                block,
+                index,
+                inst_kind: call.inst_kind(),
+                edge_kind,
+            };
+            for target in targets {
+                match graph.edge_weight_mut(caller, target) {
+                    Some(sites) => sites.push(site),
+                    None => {
+                        graph.add_edge(caller, target, vec![site]);
                     }
                 }
-                Either::Left(_) => {} // ignore calls to inline assembly
             }
         };
 
-        // Find all call (and Invoke) instructions and add the appropriate edges
-        for module in modules {
-            for f in &module.functions {
+        // Find all call (and Invoke and CallBr) instructions and add the
+        // appropriate edges
+        for ModuleFunctions { module, functions } in modules {
+            for f in functions {
                 graph.add_node(&f.name); // just to ensure all functions end up getting nodes in the graph by the end
                 for bb in &f.basic_blocks {
-                    for inst in &bb.instrs {
+                    for (index, inst) in bb.instrs.iter().enumerate() {
                         if let Instruction::Call(call) = inst {
-                            add_edge_for_call(
+                            add_edges_for_call(
                                 &mut graph,
                                 &f.name,
+                                &bb.name,
+                                index,
                                 CallOrInvoke::Call { call, module },
                             );
                         }
                     }
-                    if let Terminator::Invoke(invoke) = &bb.term {
-                        add_edge_for_call(
+                    let index = bb.instrs.len();
+                    match &bb.term {
+                        Terminator::Invoke(invoke) => add_edges_for_call(
                             &mut graph,
                             &f.name,
+                            &bb.name,
+                            index,
                             CallOrInvoke::Invoke { invoke, module },
-                        );
+                        ),
+                        #[cfg(not(feature = "llvm-8"))]
+                        Terminator::CallBr(callbr) => add_edges_for_call(
+                            &mut graph,
+                            &f.name,
+                            &bb.name,
+                            index,
+                            CallOrInvoke::CallBr { callbr, module },
+                        ),
+                        _ => {}
                     }
                 }
             }
         }
+        // `tarjan_scc()` returns the SCCs in reverse topological order, i.e.,
+        // callees before callers, which is the bottom-up order we want
+        let sccs: Vec<Vec<&'m str>> = tarjan_scc(&graph)
+            .into_iter()
+            .map(|mut scc| {
+                scc.sort_unstable();
+                scc
+            })
+            .collect();
+        let scc_index = sccs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, scc)| scc.iter().map(move |&func_name| (func_name, i)))
+            .collect();
 
-        Self { graph }
+        Self {
+            graph,
+            sccs,
+            scc_index,
+        }
     }
 
     /// Get the names of functions in the analyzed `Module`(s) which may call the
     /// given function.
     ///
     /// This analysis conservatively assumes that function pointers may point to
-    /// any function in the analyzed `Module`(s) that has the appropriate type.
+    /// any function in the analyzed `Module`(s) that has the appropriate type,
+    /// and (depending on the `IndirectCallTargets` the graph was built with)
+    /// whose address is taken.
     ///
     /// Error if the given function is not found in the analyzed `Module`(s).
     pub fn callers<'s>(&'s self, func_name: &'m str) -> Result<impl Iterator<Item = &'m str> + 's> {
-        if !self.graph.contains_node(func_name) {
-            return Err(error::Error::CallGraph(format!(
-                "callers(): function named {:?} not found in the Module(s)",
-                func_name
-            )));
-        }
+        self.check_function(func_name)?;
         Ok(self
             .graph
             .neighbors_directed(func_name, Direction::Incoming))
@@ -123,20 +281,314 @@ impl<'m> CallGraph<'m> {
     /// called by the given function.
     ///
     /// This analysis conservatively assumes that function pointers may point to
-    /// any function in the analyzed `Module`(s) that has the appropriate type.
+    /// any function in the analyzed `Module`(s) that has the appropriate type,
+    /// and (depending on the `IndirectCallTargets` the graph was built with)
+    /// whose address is taken.
     ///
-    /// Panics if the given function is not found in the analyzed `Module`(s).
+    /// Error if the given function is not found in the analyzed `Module`(s).
     pub fn callees<'s>(&'s self, func_name: &'m str) -> Result<impl Iterator<Item = &'m str> + 's> {
-        if !self.graph.contains_node(func_name) {
-            return Err(error::Error::CallGraph(format!(
-                "callees(): function named {:?} not found in the Module(s)",
-                func_name
-            )));
-        }
+        self.check_function(func_name)?;
         Ok(self
             .graph
             .neighbors_directed(func_name, Direction::Outgoing))
     }
+
+    /// Get the `CallSite`s in function `caller` which may call function
+    /// `callee`.
+    ///
+    /// See notes on `callers()`.
+    ///
+    /// Error if either function is not found in the analyzed `Module`(s).
+    pub fn call_sites<'s>(
+        &'s self,
+        caller: &'m str,
+        callee: &'m str,
+    ) -> Result<impl Iterator<Item = &'s CallSite<'m>> + 's> {
+        self.check_function(caller)?;
+        self.check_function(callee)?;
+        Ok(self.graph.edge_weight(caller, callee).into_iter().flatten())
+    }
+
+    /// Get all the `CallSite`s in the given function, along with the name of
+    /// the function which each may call.
+    ///
+    /// A `CallSite` which may call several functions (e.g., a call through a
+    /// function pointer) appears once for each of them.
+    ///
+    /// Error if the given function is not found in the analyzed `Module`(s).
+    pub fn call_sites_in<'s>(
+        &'s self,
+        func_name: &'m str,
+    ) -> Result<impl Iterator<Item = (&'m str, &'s CallSite<'m>)> + 's> {
+        self.check_function(func_name)?;
+        Ok(self
+            .graph
+            .edges_directed(func_name, Direction::Outgoing)
+            .flat_map(|(_, callee, sites)| sites.iter().map(move |site| (callee, site))))
+    }
+
+    /// Get all the `CallSite`s in the analyzed `Module`(s) which may call the
+    /// given function. The calling function is available as
+    /// `CallSite.caller`.
+    ///
+    /// Error if the given function is not found in the analyzed `Module`(s).
+    pub fn call_sites_of<'s>(
+        &'s self,
+        func_name: &'m str,
+    ) -> Result<impl Iterator<Item = &'s CallSite<'m>> + 's> {
+        self.check_function(func_name)?;
+        Ok(self
+            .graph
+            .edges_directed(func_name, Direction::Incoming)
+            .flat_map(|(_, _, sites)| sites.iter()))
+    }
+
+    /// Get the names of all the functions which may be (directly or
+    /// transitively) called by any of the given `roots`. The `roots`
+    /// themselves are always included.
+    ///
+    /// See notes on `callers()`.
+    ///
+    /// Error if any of the `roots` is not found in the analyzed `Module`(s).
+    pub fn reachable_from(
+        &self,
+        roots: impl IntoIterator<Item = &'m str>,
+    ) -> Result<HashSet<&'m str>> {
+        self.transitive_closure(roots, Direction::Outgoing)
+    }
+
+    /// Get the names of all the functions which may (directly or transitively)
+    /// call any of the given `targets`. The `targets` themselves are always
+    /// included.
+    ///
+    /// See notes on `callers()`.
+    ///
+    /// Error if any of the `targets` is not found in the analyzed `Module`(s).
+    pub fn can_reach(
+        &self,
+        targets: impl IntoIterator<Item = &'m str>,
+    ) -> Result<HashSet<&'m str>> {
+        self.transitive_closure(targets, Direction::Incoming)
+    }
+
+    /// Get a shortest chain of calls by which function `from` may
+    /// (transitively) call function `to`, as the list of functions along the
+    /// chain, starting with `from` and ending with `to`. If `from` and `to`
+    /// are the same function, the chain is just that function.
+    ///
+    /// Returns `Ok(None)` if `from` can't reach `to`. If there are several
+    /// shortest chains, which one is returned is unspecified.
+    ///
+    /// Error if either function is not found in the analyzed `Module`(s).
+    pub fn shortest_call_chain(&self, from: &'m str, to: &'m str) -> Result<Option<Vec<&'m str>>> {
+        self.shortest_path(from, to)
+    }
+
+    /// Like `shortest_call_chain()`, but returns the chain as the `CallSite`
+    /// making each call along it: the first `CallSite` is in `from`, and the
+    /// last one may call `to`. If `from` and `to` are the same function, the
+    /// chain is empty.
+    ///
+    /// Where a function along the chain has several `CallSite`s which may call
+    /// the next function, which one is returned is unspecified.
+    ///
+    /// Error if either function is not found in the analyzed `Module`(s).
+    pub fn shortest_call_chain_sites(
+        &self,
+        from: &'m str,
+        to: &'m str,
+    ) -> Result<Option<Vec<&CallSite<'m>>>> {
+        Ok(self.shortest_path(from, to)?.map(|chain| {
+            chain
+                .windows(2)
+                .map(|pair| {
+                    &self
+                        .graph
+                        .edge_weight(pair[0], pair[1])
+                        .expect("chain should follow edges of the call graph")[0]
+                })
+                .collect()
+        }))
+    }
+
+    /// Get all the functions reachable from the given `start` functions by
+    /// following edges in the given `direction`, including the `start`
+    /// functions themselves.
+    fn transitive_closure(
+        &self,
+        start: impl IntoIterator<Item = &'m str>,
+        direction: Direction,
+    ) -> Result<HashSet<&'m str>> {
+        let mut worklist: Vec<&'m str> = vec![];
+        for func_name in start {
+            self.check_function(func_name)?;
+            worklist.push(func_name);
+        }
+        let mut seen: HashSet<&'m str> = worklist.iter().copied().collect();
+        while let Some(func_name) = worklist.pop() {
+            for next in self.graph.neighbors_directed(func_name, direction) {
+                if seen.insert(next) {
+                    worklist.push(next);
+                }
+            }
+        }
+        Ok(seen)
+    }
+
+    /// Breadth-first search for a shortest path from `from` to `to`
+    fn shortest_path(&self, from: &'m str, to: &'m str) -> Result<Option<Vec<&'m str>>> {
+        self.check_function(from)?;
+        self.check_function(to)?;
+        // map from each function we've seen to the function we first saw it
+        // called from
+        let mut pred: HashMap<&'m str, &'m str> = HashMap::new();
+        let mut queue: VecDeque<&'m str> = VecDeque::new();
+        queue.push_back(from);
+        while let Some(func_name) = queue.pop_front() {
+            if func_name == to {
+                let mut chain = vec![to];
+                let mut cur = to;
+                while cur != from {
+                    cur = pred[cur];
+                    chain.push(cur);
+                }
+                chain.reverse();
+                return Ok(Some(chain));
+            }
+            for callee in self
+                .graph
+                .neighbors_directed(func_name, Direction::Outgoing)
+            {
+                if callee != from && !pred.contains_key(callee) {
+                    pred.insert(callee, func_name);
+                    queue.push_back(callee);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Error if the given function is not found in the analyzed `Module`(s)
+    fn check_function(&self, func_name: &str) -> Result<()> {
+        if self.graph.contains_node(func_name) {
+            Ok(())
+        } else {
+            Err(Error::FunctionNotFound {
+                name: func_name.to_string(),
+            })
+        }
+    }
+
+    /// Get the strongly connected components of the call graph. Each SCC is a
+    /// maximal set of functions which may all (directly or indirectly) call
+    /// each other; a function which isn't part of any cycle is in an SCC by
+    /// itself. The functions in each SCC are sorted by name.
+    ///
+    /// The SCCs are returned in bottom-up order, the same as
+    /// `bottom_up_order()`.
+    pub fn sccs<'s>(&'s self) -> impl Iterator<Item = &'s [&'m str]> + 's {
+        self.bottom_up_order()
+    }
+
+    /// Get the strongly connected component containing the given function.
+    /// See notes on `sccs()`.
+    ///
+    /// Error if the given function is not found in the analyzed `Module`(s).
+    pub fn scc_of(&self, func_name: &'m str) -> Result<&[&'m str]> {
+        match self.scc_index.get(func_name) {
+            Some(&i) => Ok(&self.sccs[i]),
+            None => Err(Error::FunctionNotFound {
+                name: func_name.to_string(),
+            }),
+        }
+    }
+
+    /// Is the given function recursive, i.e., may it (directly or indirectly)
+    /// call itself?
+    ///
+    /// See notes on `callers()`.
+    ///
+    /// Error if the given function is not found in the analyzed `Module`(s).
+    pub fn is_recursive(&self, func_name: &'m str) -> Result<bool> {
+        match self.scc_index.get(func_name) {
+            Some(&i) => Ok(self.is_recursive_scc(&self.sccs[i])),
+            None => Err(Error::FunctionNotFound {
+                name: func_name.to_string(),
+            }),
+        }
+    }
+
+    /// Get the recursive cycles in the call graph: each item is a strongly
+    /// connected component (see `sccs()`) in which every function may
+    /// (directly or indirectly) call itself and every other function in the
+    /// component. This includes single functions which call themselves
+    /// directly.
+    ///
+    /// The cycles are returned in bottom-up order.
+    pub fn recursive_cycles<'s>(&'s self) -> impl Iterator<Item = &'s [&'m str]> + 's {
+        self.sccs().filter(move |scc| self.is_recursive_scc(scc))
+    }
+
+    /// Get the strongly connected components of the call graph (see `sccs()`)
+    /// in bottom-up order: each SCC comes after all the SCCs containing
+    /// functions which it may call. This is a topological order of the call
+    /// graph with its SCCs condensed, with callees before callers; it is the
+    /// right order in which to compute function summaries.
+    pub fn bottom_up_order<'s>(&'s self) -> impl Iterator<Item = &'s [&'m str]> + 's {
+        self.sccs.iter().map(Vec::as_slice)
+    }
+
+    /// Get the strongly connected components of the call graph (see `sccs()`)
+    /// in top-down order: each SCC comes before all the SCCs containing
+    /// functions which it may call. This is the reverse of
+    /// `bottom_up_order()`.
+    pub fn top_down_order<'s>(&'s self) -> impl Iterator<Item = &'s [&'m str]> + 's {
+        self.sccs.iter().rev().map(Vec::as_slice)
+    }
+
+    /// Is the given SCC recursive: does it have more than one function, or a
+    /// single function which calls itself?
+    fn is_recursive_scc(&self, scc: &[&'m str]) -> bool {
+        match scc {
+            [func_name] => self.graph.contains_edge(func_name, func_name),
+            _ => true,
+        }
+    }
+
+    /// Render the call graph in the Graphviz DOT format, with the given
+    /// options
+    pub fn to_dot(&self, options: &DotOptions<'m>) -> String {
+        dot_to_string(|w| self.write_dot(w, options))
+    }
+
+    /// Write the call graph in the Graphviz DOT format to `w`, with the given
+    /// options
+    pub fn write_dot(&self, w: &mut impl Write, options: &DotOptions<'m>) -> io::Result<()> {
+        write_digraph(
+            w,
+            "call graph",
+            "shape=ellipse",
+            self.graph.nodes().map(|func_name| {
+                let highlighted = options.highlight_functions.contains(func_name);
+                (func_name.to_owned(), escape(func_name), highlighted)
+            }),
+            self.graph.all_edges().map(|(from, to, sites)| {
+                let label = if options.edge_kinds {
+                    let mut kinds: Vec<String> = vec![];
+                    for site in sites {
+                        let kind = site.edge_kind.to_string();
+                        if !kinds.contains(&kind) {
+                            kinds.push(kind);
+                        }
+                    }
+                    Some(kinds.join(", "))
+                } else {
+                    None
+                };
+                (from.to_owned(), to.to_owned(), label)
+            }),
+        )
+    }
 }
 
 enum CallOrInvoke<'a> {
@@ -150,6 +602,12 @@ enum CallOrInvoke<'a> {
         module: &'a Module,
         invoke: &'a Invoke,
     },
+    #[cfg(not(feature = "llvm-8"))]
+    CallBr {
+        #[cfg_attr(feature = "llvm-15-or-greater", allow(dead_code))]
+        module: &'a Module,
+        callbr: &'a CallBr,
+    },
 }
 
 impl<'a> CallOrInvoke<'a> {
@@ -158,6 +616,17 @@ impl<'a> CallOrInvoke<'a> {
         match self {
             Self::Call { module, .. } => module,
             Self::Invoke { module, .. } => module,
+            #[cfg(not(feature = "llvm-8"))]
+            Self::CallBr { module, .. } => module,
+        }
+    }
+
+    fn inst_kind(&self) -> CallInstKind {
+        match self {
+            Self::Call { .. } => CallInstKind::Call,
+            Self::Invoke { .. } => CallInstKind::Invoke,
+            #[cfg(not(feature = "llvm-8"))]
+            Self::CallBr { .. } => CallInstKind::CallBr,
         }
     }
 
@@ -165,13 +634,21 @@ impl<'a> CallOrInvoke<'a> {
         match self {
             Self::Call { call, .. } => &call.function,
             Self::Invoke { invoke, .. } => &invoke.function,
+            #[cfg(not(feature = "llvm-8"))]
+            Self::CallBr { callbr, .. } => &callbr.function,
         }
     }
 
-    fn callee_ty(&self) -> TypeRef {
+    /// Get the type of the function being called.
+    ///
+    /// Returns `None` if the type is not known; currently this only happens
+    /// for `CallBr` on LLVM 15+, where `llvm-ir` doesn't record the function
+    /// type of the `CallBr`. Such calls through function pointers are assumed
+    /// to go to functions of any type.
+    fn callee_ty(&self) -> Option<TypeRef> {
         #[cfg(feature = "llvm-14-or-lower")]
         match self.module().type_of(self.callee()).as_ref() {
-            llvm_ir::Type::PointerType { pointee_type, .. } => pointee_type.clone(),
+            llvm_ir::Type::PointerType { pointee_type, .. } => Some(pointee_type.clone()),
             ty => panic!(
                 "Expected function pointer to have pointer type, but got {:?}",
                 ty
@@ -179,8 +656,9 @@ impl<'a> CallOrInvoke<'a> {
         }
         #[cfg(feature = "llvm-15-or-greater")]
         match self {
-            Self::Call { call, .. } => call.function_ty.clone(),
-            Self::Invoke { invoke, .. } => invoke.function_ty.clone(),
+            Self::Call { call, .. } => Some(call.function_ty.clone()),
+            Self::Invoke { invoke, .. } => Some(invoke.function_ty.clone()),
+            Self::CallBr { .. } => None,
         }
     }
 }
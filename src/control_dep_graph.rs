@@ -1,9 +1,11 @@
 use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
 use crate::dominator_tree::PostDominatorTree;
-use llvm_ir::Name;
-use petgraph::prelude::{DfsPostOrder, DiGraphMap, Direction};
-use petgraph::visit::Walker;
+use crate::dot::{cfgnode_dot_node, dot_to_string, write_digraph, DotOptions};
+use crate::error::{Error, Result};
+use llvm_ir::{Function, Name};
+use petgraph::prelude::{DiGraphMap, Direction};
 use std::collections::HashSet;
+use std::io::{self, Write};
 
 /// The control dependence graph for a particular function.
 /// https://en.wikipedia.org/wiki/Data_dependency#Control_Dependency
@@ -19,33 +21,20 @@ pub struct ControlDependenceGraph<'m> {
 
     /// Entry node for the function
     pub(crate) entry_node: CFGNode<'m>,
+
+    /// The function itself
+    function: &'m Function,
 }
 
 impl<'m> ControlDependenceGraph<'m> {
     pub(crate) fn new(cfg: &ControlFlowGraph<'m>, postdomtree: &PostDominatorTree<'m>) -> Self {
-        // algorithm thanks to Cytron, Ferrante, Rosen, et al. "Efficiently Computing Static Single Assignment Form and the Control Dependence Graph"
-        // https://www.cs.utexas.edu/~pingali/CS380C/2010/papers/ssaCytron.pdf (Figure 10)
+        // A block has an immediate control dependence on exactly the blocks in
+        // its postdominance frontier (Cytron et al, p. 477)
 
         let mut graph = DiGraphMap::new();
 
-        for block_x in
-            DfsPostOrder::new(&postdomtree.graph, CFGNode::Return).iter(&postdomtree.graph)
-        {
-            let mut postdominance_frontier_of_x = vec![];
-            for block_y in cfg.preds_as_nodes(block_x) {
-                if postdomtree.ipostdom_of_cfgnode(block_y) != Some(block_x) {
-                    postdominance_frontier_of_x.push(block_y);
-                }
-            }
-            for block_z in postdomtree.children_of_cfgnode(block_x) {
-                // we should have already computed all of the outgoing edges from block_z
-                for block_y in graph.neighbors_directed(block_z, Direction::Outgoing) {
-                    if postdomtree.ipostdom_of_cfgnode(block_y) != Some(block_x) {
-                        postdominance_frontier_of_x.push(block_y);
-                    }
-                }
-            }
-            for node in postdominance_frontier_of_x {
+        for block_x in postdomtree.nodes() {
+            for node in postdomtree.postdominance_frontier_of_cfgnode(block_x) {
                 graph.add_edge(block_x, node, ());
             }
         }
@@ -53,6 +42,7 @@ impl<'m> ControlDependenceGraph<'m> {
         Self {
             graph,
             entry_node: cfg.entry_node,
+            function: cfg.function,
         }
     }
 
@@ -72,7 +62,7 @@ impl<'m> ControlDependenceGraph<'m> {
             .neighbors_directed(node, Direction::Outgoing)
             .map(|node| match node {
                 CFGNode::Block(block) => block,
-                CFGNode::Return => panic!("Nothing should be control-dependent on Return"),
+                node => panic!("Nothing should be control-dependent on {}", node),
             })
     }
 
@@ -146,12 +136,50 @@ impl<'m> ControlDependenceGraph<'m> {
     }
 
     /// Get the `Name` of the entry block for the function
+    ///
+    /// Panics if the entry is not a block; see `try_entry()` for a
+    /// non-panicking version.
     pub fn entry(&self) -> &'m Name {
+        self.try_entry().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the `Name` of the entry block for the function
+    ///
+    /// Error if the entry is not a block.
+    pub fn try_entry(&self) -> Result<&'m Name> {
         match self.entry_node {
-            CFGNode::Block(block) => block,
-            CFGNode::Return => panic!("Return node should not be entry"), // perhaps you tried to call this on a reversed CFG? In-crate users can use the `entry_node` field directly if they need to account for the possibility of a reversed CFG
+            CFGNode::Block(block) => Ok(block),
+            node => Err(Error::MalformedCFG {
+                function: self.function.name.clone(),
+                message: format!("{} node should not be entry", node),
+            }), // perhaps you tried to call this on a reversed CFG? In-crate users can use the `entry_node` field directly if they need to account for the possibility of a reversed CFG
         }
     }
+
+    /// Render the control dependence graph in the Graphviz DOT format, with
+    /// the given options.
+    ///
+    /// An edge from bbX to bbY indicates that bbX has an immediate control
+    /// dependence on bbY.
+    pub fn to_dot(&self, options: &DotOptions<'m>) -> String {
+        dot_to_string(|w| self.write_dot(w, options))
+    }
+
+    /// Write the control dependence graph in the Graphviz DOT format to `w`,
+    /// with the given options
+    pub fn write_dot(&self, w: &mut impl Write, options: &DotOptions<'m>) -> io::Result<()> {
+        write_digraph(
+            w,
+            &self.function.name,
+            "shape=box",
+            self.graph
+                .nodes()
+                .map(|node| cfgnode_dot_node(self.function, node, options)),
+            self.graph
+                .all_edges()
+                .map(|(from, to, _)| (from.to_string(), to.to_string(), None)),
+        )
+    }
 }
 
 struct ControlDependenciesIterator<'m> {
@@ -1,6 +1,11 @@
-use llvm_ir::{Function, Name, Terminator};
+use crate::dataflow::{self, DataflowAnalysis, DataflowResult};
+use crate::dot::{cfgnode_dot_node, dot_to_string, write_digraph, DotOptions};
+use crate::error::{Error, Result};
+use llvm_ir::{ConstantRef, Function, Name, Terminator};
 use petgraph::prelude::{DiGraphMap, Direction};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::io::{self, Write};
 
 /// The control flow graph for a particular function.
 ///
@@ -13,19 +18,49 @@ pub struct ControlFlowGraph<'m> {
     ///
     /// Or, an edge from bbX to `Return` indicates that the function may return
     /// from bbX
-    pub(crate) graph: DiGraphMap<CFGNode<'m>, ()>,
+    ///
+    /// Depending on the `ExceptionalFlow` the graph was built with, blocks
+    /// which unwind to the caller have an edge to `Return`, an edge to
+    /// `Unwind`, or no edge at all
+    ///
+    /// Each edge is labeled with the `CFGEdgeKind`(s) explaining why control
+    /// may flow along it. An edge may have more than one kind, e.g. if several
+    /// cases of a `Switch` go to the same block.
+    ///
+    /// For `CallBr` terminators, the destinations are not reliably available
+    /// from `llvm-ir`, so a block ending in `CallBr` has edges to every
+    /// non-entry block of the function
+    pub(crate) graph: DiGraphMap<CFGNode<'m>, Vec<CFGEdgeKind<'m>>>,
 
     /// Entry node for the function
     pub(crate) entry_node: CFGNode<'m>,
+
+    /// The nodes reachable from the entry node, in depth-first preorder
+    preorder: Vec<CFGNode<'m>>,
+
+    /// The nodes reachable from the entry node, in depth-first postorder
+    postorder: Vec<CFGNode<'m>>,
+
+    /// Map from each node reachable from the entry node to its index in
+    /// reverse postorder. The entry node is number 0.
+    pub(crate) rpo_numbers: HashMap<CFGNode<'m>, usize>,
+
+    /// The function itself
+    pub(crate) function: &'m Function,
 }
 
-/// A CFGNode represents a basic block, or the special node `Return`
+/// A CFGNode represents a basic block, or one of the special nodes `Return`
+/// and `Unwind`
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
 pub enum CFGNode<'m> {
     /// The block with the given `Name`
     Block(&'m Name),
     /// The special `Return` node indicating function return
     Return,
+    /// The special `Unwind` node indicating that an exception propagates out
+    /// of the function. This node only appears in graphs built with
+    /// `ExceptionalFlow::SeparateUnwind`.
+    Unwind,
 }
 
 impl<'m> fmt::Display for CFGNode<'m> {
@@ -33,100 +68,258 @@ impl<'m> fmt::Display for CFGNode<'m> {
         match self {
             CFGNode::Block(block) => write!(f, "{}", block),
             CFGNode::Return => write!(f, "Return"),
+            CFGNode::Unwind => write!(f, "Unwind"),
         }
     }
 }
 
+/// A CFGEdgeKind describes why control may flow along a particular edge of the
+/// `ControlFlowGraph`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CFGEdgeKind<'m> {
+    /// Unconditional `Br`
+    Branch,
+    /// The `true_dest` of a `CondBr`
+    BranchTrue,
+    /// The `false_dest` of a `CondBr`
+    BranchFalse,
+    /// The destination of a `Switch` for the given case value
+    SwitchCase(&'m ConstantRef),
+    /// The `default_dest` of a `Switch`
+    SwitchDefault,
+    /// One of the `possible_dests` of an `IndirectBr`
+    IndirectBranch,
+    /// A `Ret`, i.e., an edge to `CFGNode::Return`
+    Return,
+    /// A `Resume`, i.e., an edge to `CFGNode::Return` or `CFGNode::Unwind`
+    /// propagating an exception
+    Resume,
+    /// The `return_label` of an `Invoke`, taken if the callee returns normally
+    InvokeNormal,
+    /// The `exception_label` of an `Invoke`, taken if the callee unwinds
+    InvokeUnwind,
+    /// The `unwind_dest` of a `CleanupRet`, or an edge to `CFGNode::Return` or
+    /// `CFGNode::Unwind` if the `CleanupRet` unwinds to the caller
+    CleanupRetUnwind,
+    /// The `successor` of a `CatchRet`
+    CatchRet,
+    /// One of the `catch_handlers` of a `CatchSwitch`
+    CatchSwitchHandler,
+    /// The `default_unwind_dest` of a `CatchSwitch`, or an edge to
+    /// `CFGNode::Return` or `CFGNode::Unwind` if the `CatchSwitch` unwinds to
+    /// the caller
+    CatchSwitchUnwind,
+    /// A possible destination of a `CallBr`. See notes on `ControlFlowGraph`:
+    /// these edges are conservative and go to every non-entry block.
+    CallBr,
+}
+
+impl<'m> fmt::Display for CFGEdgeKind<'m> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CFGEdgeKind::Branch => write!(f, "br"),
+            CFGEdgeKind::BranchTrue => write!(f, "true"),
+            CFGEdgeKind::BranchFalse => write!(f, "false"),
+            CFGEdgeKind::SwitchCase(val) => write!(f, "case {}", val),
+            CFGEdgeKind::SwitchDefault => write!(f, "default"),
+            CFGEdgeKind::IndirectBranch => write!(f, "indirectbr"),
+            CFGEdgeKind::Return => write!(f, "ret"),
+            CFGEdgeKind::Resume => write!(f, "resume"),
+            CFGEdgeKind::InvokeNormal => write!(f, "normal"),
+            CFGEdgeKind::InvokeUnwind => write!(f, "unwind"),
+            CFGEdgeKind::CleanupRetUnwind => write!(f, "cleanupret unwind"),
+            CFGEdgeKind::CatchRet => write!(f, "catchret"),
+            CFGEdgeKind::CatchSwitchHandler => write!(f, "catch handler"),
+            CFGEdgeKind::CatchSwitchUnwind => write!(f, "catchswitch unwind"),
+            CFGEdgeKind::CallBr => write!(f, "callbr"),
+        }
+    }
+}
+
+impl<'m> CFGEdgeKind<'m> {
+    /// Is this an edge which is only taken when an exception is thrown or
+    /// propagated?
+    ///
+    /// These are the edges which are omitted from graphs built with
+    /// `ExceptionalFlow::Omit`.
+    pub fn is_exceptional(&self) -> bool {
+        matches!(
+            self,
+            CFGEdgeKind::Resume
+                | CFGEdgeKind::InvokeUnwind
+                | CFGEdgeKind::CleanupRetUnwind
+                | CFGEdgeKind::CatchSwitchHandler
+                | CFGEdgeKind::CatchSwitchUnwind
+        )
+    }
+}
+
+/// How exceptional control flow is represented in a `ControlFlowGraph` (and
+/// therefore in the dominator trees and control dependence graph computed
+/// from it)
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum ExceptionalFlow {
+    /// Exceptional edges are included, and blocks which unwind to the caller
+    /// have an edge to `CFGNode::Return`, just like blocks which return
+    /// normally. This is what `FunctionAnalysis::control_flow_graph()` and
+    /// friends use.
+    MergeWithReturn,
+    /// Exceptional edges are included, and blocks which unwind to the caller
+    /// have an edge to the separate `CFGNode::Unwind` node.
+    ///
+    /// In the `PostDominatorTree`, `CFGNode::Unwind` is treated as flowing to
+    /// `CFGNode::Return`, so that the tree still has a single root.
+    SeparateUnwind,
+    /// Exceptional edges (see `CFGEdgeKind::is_exceptional()`) are omitted,
+    /// leaving only normal control flow. Blocks which are only reachable by
+    /// exceptional edges, such as landing pads, become unreachable.
+    Omit,
+}
+
 impl<'m> ControlFlowGraph<'m> {
-    pub(crate) fn new(function: &'m Function) -> Self {
-        let mut graph: DiGraphMap<CFGNode<'m>, ()> = DiGraphMap::with_capacity(
+    pub(crate) fn new(function: &'m Function, exceptional_flow: ExceptionalFlow) -> Self {
+        let unwind_node = match exceptional_flow {
+            ExceptionalFlow::SeparateUnwind => CFGNode::Unwind,
+            _ => CFGNode::Return,
+        };
+        let mut graph: DiGraphMap<CFGNode<'m>, Vec<CFGEdgeKind<'m>>> = DiGraphMap::with_capacity(
             function.basic_blocks.len() + 1,
             2 * function.basic_blocks.len(), // arbitrary guess
         );
 
         for bb in &function.basic_blocks {
+            let from = CFGNode::Block(&bb.name);
+            let mut add_edge = |to: CFGNode<'m>, kind: CFGEdgeKind<'m>| {
+                if exceptional_flow == ExceptionalFlow::Omit && kind.is_exceptional() {
+                    return;
+                }
+                match graph.edge_weight_mut(from, to) {
+                    Some(kinds) => kinds.push(kind),
+                    None => {
+                        graph.add_edge(from, to, vec![kind]);
+                    }
+                }
+            };
             match &bb.term {
                 Terminator::Br(br) => {
-                    graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Block(&br.dest), ());
+                    add_edge(CFGNode::Block(&br.dest), CFGEdgeKind::Branch);
                 }
                 Terminator::CondBr(condbr) => {
-                    graph.add_edge(
-                        CFGNode::Block(&bb.name),
-                        CFGNode::Block(&condbr.true_dest),
-                        (),
-                    );
-                    graph.add_edge(
-                        CFGNode::Block(&bb.name),
-                        CFGNode::Block(&condbr.false_dest),
-                        (),
-                    );
+                    add_edge(CFGNode::Block(&condbr.true_dest), CFGEdgeKind::BranchTrue);
+                    add_edge(CFGNode::Block(&condbr.false_dest), CFGEdgeKind::BranchFalse);
                 }
                 Terminator::IndirectBr(ibr) => {
                     for dest in &ibr.possible_dests {
-                        graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Block(dest), ());
+                        add_edge(CFGNode::Block(dest), CFGEdgeKind::IndirectBranch);
                     }
                 }
                 Terminator::Switch(switch) => {
-                    graph.add_edge(
-                        CFGNode::Block(&bb.name),
+                    add_edge(
                         CFGNode::Block(&switch.default_dest),
-                        (),
+                        CFGEdgeKind::SwitchDefault,
                     );
-                    for (_, dest) in &switch.dests {
-                        graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Block(dest), ());
+                    for (val, dest) in &switch.dests {
+                        add_edge(CFGNode::Block(dest), CFGEdgeKind::SwitchCase(val));
                     }
                 }
-                Terminator::Ret(_) | Terminator::Resume(_) => {
-                    graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Return, ());
+                Terminator::Ret(_) => {
+                    add_edge(CFGNode::Return, CFGEdgeKind::Return);
+                }
+                Terminator::Resume(_) => {
+                    add_edge(unwind_node, CFGEdgeKind::Resume);
                 }
                 Terminator::Invoke(invoke) => {
-                    graph.add_edge(
-                        CFGNode::Block(&bb.name),
+                    add_edge(
                         CFGNode::Block(&invoke.return_label),
-                        (),
+                        CFGEdgeKind::InvokeNormal,
                     );
-                    graph.add_edge(
-                        CFGNode::Block(&bb.name),
+                    add_edge(
                         CFGNode::Block(&invoke.exception_label),
-                        (),
+                        CFGEdgeKind::InvokeUnwind,
                     );
                 }
                 Terminator::CleanupRet(cleanupret) => {
                     if let Some(dest) = &cleanupret.unwind_dest {
-                        graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Block(dest), ());
+                        add_edge(CFGNode::Block(dest), CFGEdgeKind::CleanupRetUnwind);
                     } else {
-                        graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Return, ());
+                        add_edge(unwind_node, CFGEdgeKind::CleanupRetUnwind);
                     }
                 }
                 Terminator::CatchRet(catchret) => {
                     // Despite its name, my reading of the LLVM 10 LangRef indicates that CatchRet cannot directly return from the function
-                    graph.add_edge(
-                        CFGNode::Block(&bb.name),
-                        CFGNode::Block(&catchret.successor),
-                        (),
-                    );
+                    add_edge(CFGNode::Block(&catchret.successor), CFGEdgeKind::CatchRet);
                 }
                 Terminator::CatchSwitch(catchswitch) => {
                     if let Some(dest) = &catchswitch.default_unwind_dest {
-                        graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Block(dest), ());
+                        add_edge(CFGNode::Block(dest), CFGEdgeKind::CatchSwitchUnwind);
                     } else {
-                        graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Return, ());
+                        add_edge(unwind_node, CFGEdgeKind::CatchSwitchUnwind);
                     }
                     for handler in &catchswitch.catch_handlers {
-                        graph.add_edge(CFGNode::Block(&bb.name), CFGNode::Block(handler), ());
+                        add_edge(CFGNode::Block(handler), CFGEdgeKind::CatchSwitchHandler);
                     }
                 }
                 #[cfg(not(feature = "llvm-8"))]
-                Terminator::CallBr(_) => unimplemented!("CallBr instruction"),
+                Terminator::CallBr(_) => {
+                    // `llvm-ir` doesn't tell us the indirect labels of the
+                    // `CallBr` (see notes on `CallBr.other_labels`), and its
+                    // `return_label` is only reliable when there is exactly
+                    // one indirect label. Any block other than the entry
+                    // block may be a destination, so we conservatively add
+                    // edges to all of them.
+                    for dest in function.basic_blocks.iter().skip(1) {
+                        add_edge(CFGNode::Block(&dest.name), CFGEdgeKind::CallBr);
+                    }
+                }
                 Terminator::Unreachable(_) => {
                     // no successors
                 }
             }
         }
 
+        Self::from_graph(
+            graph,
+            CFGNode::Block(&function.basic_blocks[0].name),
+            function,
+        )
+    }
+
+    /// Construct a `ControlFlowGraph` from the given graph and entry node,
+    /// computing the traversal orders
+    fn from_graph(
+        graph: DiGraphMap<CFGNode<'m>, Vec<CFGEdgeKind<'m>>>,
+        entry_node: CFGNode<'m>,
+        function: &'m Function,
+    ) -> Self {
+        // We do our own depth-first search rather than using petgraph's `Dfs`,
+        // which visits nodes in an order that is not a true preorder, and
+        // wouldn't give a preorder consistent with the postorder
+        let mut preorder = vec![entry_node];
+        let mut postorder = Vec::with_capacity(graph.node_count());
+        let mut visited: HashSet<CFGNode<'m>> = std::iter::once(entry_node).collect();
+        let mut stack = vec![(entry_node, graph.neighbors(entry_node))];
+        while let Some((node, succs)) = stack.last_mut() {
+            match succs.find(|succ| !visited.contains(succ)) {
+                Some(succ) => {
+                    visited.insert(succ);
+                    preorder.push(succ);
+                    stack.push((succ, graph.neighbors(succ)));
+                }
+                None => {
+                    postorder.push(*node);
+                    stack.pop();
+                }
+            }
+        }
+        let rpo_numbers = postorder.iter().rev().copied().zip(0..).collect();
+
         Self {
             graph,
-            entry_node: CFGNode::Block(&function.basic_blocks[0].name),
+            entry_node,
+            preorder,
+            postorder,
+            rpo_numbers,
+            function,
         }
     }
 
@@ -141,13 +334,22 @@ impl<'m> ControlFlowGraph<'m> {
         self.preds_of_cfgnode(CFGNode::Return)
     }
 
+    /// Get the predecessors of the special `Unwind` node, i.e., get all blocks
+    /// which may directly unwind to the caller.
+    ///
+    /// This is always empty unless the graph was built with
+    /// `ExceptionalFlow::SeparateUnwind`.
+    pub fn preds_of_unwind<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.preds_of_cfgnode(CFGNode::Unwind)
+    }
+
     pub(crate) fn preds_of_cfgnode<'s>(
         &'s self,
         node: CFGNode<'m>,
     ) -> impl Iterator<Item = &'m Name> + 's {
         self.preds_as_nodes(node).map(|cfgnode| match cfgnode {
             CFGNode::Block(block) => block,
-            CFGNode::Return => panic!("Shouldn't have CFGNode::Return as a predecessor"), // perhaps you tried to call this on a reversed CFG? In-crate users can use `preds_as_nodes()` if they need to account for the possibility of a reversed CFG
+            node => panic!("Shouldn't have {} as a predecessor", node), // perhaps you tried to call this on a reversed CFG? In-crate users can use `preds_as_nodes()` if they need to account for the possibility of a reversed CFG
         })
     }
 
@@ -158,27 +360,212 @@ impl<'m> ControlFlowGraph<'m> {
         self.graph.neighbors_directed(node, Direction::Incoming)
     }
 
+    pub(crate) fn succs_as_nodes<'s>(
+        &'s self,
+        node: CFGNode<'m>,
+    ) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        self.graph.neighbors_directed(node, Direction::Outgoing)
+    }
+
     /// Get the successors of the basic block with the given `Name`.
     /// Here, `CFGNode::Return` indicates that the function may directly return
-    /// from this basic block.
+    /// from this basic block, and `CFGNode::Unwind` that it may directly
+    /// unwind to the caller.
     pub fn succs<'s>(&'s self, block: &'m Name) -> impl Iterator<Item = CFGNode<'m>> + 's {
         self.graph
             .neighbors_directed(CFGNode::Block(block), Direction::Outgoing)
     }
 
+    /// Get the predecessors of the basic block with the given `Name`, along
+    /// with the kind of the edge from each predecessor.
+    ///
+    /// A predecessor with several kinds of edges to `block` (e.g., several
+    /// `Switch` cases) appears once for each kind.
+    pub fn preds_with_kind<'s>(
+        &'s self,
+        block: &'m Name,
+    ) -> impl Iterator<Item = (&'m Name, CFGEdgeKind<'m>)> + 's {
+        self.preds_with_kind_of_cfgnode(CFGNode::Block(block))
+    }
+
+    /// Get the predecessors of the special `Return` node, along with the kind
+    /// of the edge from each predecessor.
+    ///
+    /// See notes on `preds_with_kind()`.
+    pub fn preds_of_return_with_kind<'s>(
+        &'s self,
+    ) -> impl Iterator<Item = (&'m Name, CFGEdgeKind<'m>)> + 's {
+        self.preds_with_kind_of_cfgnode(CFGNode::Return)
+    }
+
+    fn preds_with_kind_of_cfgnode<'s>(
+        &'s self,
+        node: CFGNode<'m>,
+    ) -> impl Iterator<Item = (&'m Name, CFGEdgeKind<'m>)> + 's {
+        self.graph
+            .edges_directed(node, Direction::Incoming)
+            .flat_map(|(pred, _, kinds)| {
+                let pred = match pred {
+                    CFGNode::Block(block) => block,
+                    node => panic!("Shouldn't have {} as a predecessor", node),
+                };
+                kinds.iter().map(move |kind| (pred, *kind))
+            })
+    }
+
+    /// Get the successors of the basic block with the given `Name`, along
+    /// with the kind of the edge to each successor.
+    ///
+    /// A successor with several kinds of edges from `block` (e.g., several
+    /// `Switch` cases) appears once for each kind.
+    pub fn succs_with_kind<'s>(
+        &'s self,
+        block: &'m Name,
+    ) -> impl Iterator<Item = (CFGNode<'m>, CFGEdgeKind<'m>)> + 's {
+        self.graph
+            .edges_directed(CFGNode::Block(block), Direction::Outgoing)
+            .flat_map(|(_, succ, kinds)| kinds.iter().map(move |kind| (succ, *kind)))
+    }
+
     /// Get the `Name` of the entry block for the function
+    ///
+    /// Panics if the entry is not a block; see `try_entry()` for a
+    /// non-panicking version.
     pub fn entry(&self) -> &'m Name {
+        self.try_entry().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the `Name` of the entry block for the function
+    ///
+    /// Error if the entry is not a block.
+    pub fn try_entry(&self) -> Result<&'m Name> {
         match self.entry_node {
-            CFGNode::Block(block) => block,
-            CFGNode::Return => panic!("Return node should not be entry"), // perhaps you tried to call this on a reversed CFG? In-crate users can use the `entry_node` field directly if they need to account for the possibility of a reversed CFG
+            CFGNode::Block(block) => Ok(block),
+            node => Err(Error::MalformedCFG {
+                function: self.function.name.clone(),
+                message: format!("{} node should not be entry", node),
+            }), // perhaps you tried to call this on a reversed CFG? In-crate users can use the `entry_node` field directly if they need to account for the possibility of a reversed CFG
         }
     }
 
+    /// Iterate over the nodes reachable from the entry block, in reverse
+    /// postorder. The entry block comes first, and every node comes before all
+    /// of its successors, except along back edges (loops).
+    ///
+    /// This is the usual order for solving forward dataflow problems.
+    /// `CFGNode::Return` and `CFGNode::Unwind` are included if they are
+    /// reachable.
+    pub fn reverse_postorder<'s>(&'s self) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        self.postorder.iter().rev().copied()
+    }
+
+    /// Iterate over the nodes reachable from the entry block, in depth-first
+    /// postorder. Every node comes after all of its successors, except along
+    /// back edges (loops).
+    ///
+    /// This is the usual order for solving backward dataflow problems.
+    /// `CFGNode::Return` and `CFGNode::Unwind` are included if they are
+    /// reachable.
+    pub fn postorder<'s>(&'s self) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        self.postorder.iter().copied()
+    }
+
+    /// Iterate over the nodes reachable from the entry block, in depth-first
+    /// preorder, for the same depth-first search as `postorder()`.
+    ///
+    /// `CFGNode::Return` and `CFGNode::Unwind` are included if they are
+    /// reachable.
+    pub fn preorder<'s>(&'s self) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        self.preorder.iter().copied()
+    }
+
+    /// Get the index of the basic block with the given `Name` in
+    /// `reverse_postorder()`. The entry block is number 0.
+    ///
+    /// Returns `None` if the block is unreachable.
+    pub fn rpo_number(&self, block: &'m Name) -> Option<usize> {
+        self.rpo_numbers.get(&CFGNode::Block(block)).copied()
+    }
+
+    /// Iterate over the basic blocks which are reachable from the entry
+    /// block, in reverse postorder
+    pub fn reachable_blocks<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.reverse_postorder().filter_map(|node| match node {
+            CFGNode::Block(block) => Some(block),
+            _ => None,
+        })
+    }
+
+    /// Iterate over the basic blocks which are not reachable from the entry
+    /// block, in the order they appear in the function.
+    ///
+    /// Note that with `ExceptionalFlow::Omit`, this includes blocks which are
+    /// only reachable by exceptional edges, such as landing pads.
+    pub fn unreachable_blocks<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.function
+            .basic_blocks
+            .iter()
+            .map(|bb| &bb.name)
+            .filter(move |name| !self.rpo_numbers.contains_key(&CFGNode::Block(name)))
+    }
+
+    /// Solve the given `DataflowAnalysis` over this CFG, getting the facts at
+    /// the start and end of each reachable block.
+    ///
+    /// Facts flow along all the edges of this CFG, so e.g. if it was built with
+    /// `ExceptionalFlow::Omit`, they don't flow along exceptional edges.
+    pub fn solve_dataflow<A: DataflowAnalysis<'m>>(
+        &self,
+        analysis: &A,
+    ) -> DataflowResult<'m, A::Fact> {
+        dataflow::solve(self, analysis)
+    }
+
     /// Get the reversed CFG; i.e., the CFG where all edges have been reversed
+    ///
+    /// If the CFG has an `Unwind` node, the reversed CFG also has an edge from
+    /// `Return` to `Unwind`, so that `Return` is the single entry node of the
+    /// reversed CFG
     pub(crate) fn reversed(&self) -> Self {
-        Self {
-            graph: DiGraphMap::from_edges(self.graph.all_edges().map(|(a, b, _)| (b, a, ()))),
-            entry_node: CFGNode::Return,
+        let mut graph: DiGraphMap<CFGNode<'m>, Vec<CFGEdgeKind<'m>>> = DiGraphMap::from_edges(
+            self.graph
+                .all_edges()
+                .map(|(a, b, kinds)| (b, a, kinds.clone())),
+        );
+        if graph.contains_node(CFGNode::Unwind) {
+            graph.add_edge(CFGNode::Return, CFGNode::Unwind, vec![]);
         }
+        Self::from_graph(graph, CFGNode::Return, self.function)
+    }
+
+    /// Render the CFG in the Graphviz DOT format, with the given options
+    pub fn to_dot(&self, options: &DotOptions<'m>) -> String {
+        dot_to_string(|w| self.write_dot(w, options))
+    }
+
+    /// Write the CFG in the Graphviz DOT format to `w`, with the given options
+    pub fn write_dot(&self, w: &mut impl Write, options: &DotOptions<'m>) -> io::Result<()> {
+        write_digraph(
+            w,
+            &self.function.name,
+            "shape=box",
+            self.graph
+                .nodes()
+                .map(|node| cfgnode_dot_node(self.function, node, options)),
+            self.graph.all_edges().map(|(from, to, kinds)| {
+                let label = if options.edge_kinds && !kinds.is_empty() {
+                    Some(
+                        kinds
+                            .iter()
+                            .map(|kind| kind.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    )
+                } else {
+                    None
+                };
+                (from.to_string(), to.to_string(), label)
+            }),
+        )
     }
 }
@@ -0,0 +1,336 @@
+use crate::control_flow_graph::ControlFlowGraph;
+use crate::dataflow::{DataflowAnalysis, DataflowDirection};
+use crate::def_use::{DefUseInfo, Definition, InstructionLocation};
+use crate::dot::{dot_to_string, escape, write_digraph, DotOptions};
+use either::Either;
+use llvm_ir::{BasicBlock, Function, Instruction, Name, Terminator};
+use petgraph::prelude::{DiGraphMap, Direction};
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, Write};
+
+/// The data dependence graph for a particular function, at the level of
+/// individual instructions (and terminators).
+///
+/// An instruction has a `DependenceKind::Data` dependence on each instruction
+/// whose result it uses as an operand; uses of function parameters don't
+/// create dependences. It has a `DependenceKind::Memory` dependence on each
+/// instruction which may write memory that it may read, and which may execute
+/// before it. No alias analysis is done, so this conservatively assumes that
+/// all memory accesses may alias. Calls and fences are assumed to both read
+/// and write memory, as are volatile loads, which may have side effects.
+///
+/// Memory dependences can be left out with `DependenceOptions`, giving a graph
+/// of just the SSA dependences.
+///
+/// To construct a `DataDependenceGraph`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct DataDependenceGraph<'m> {
+    /// The graph itself. An edge from X to Y indicates that instruction X has
+    /// an immediate data dependence on instruction Y. Every instruction and
+    /// terminator in the function is a node.
+    ///
+    /// Each edge is labeled with the `DependenceKind`(s) of the dependence.
+    pub(crate) graph: DiGraphMap<InstructionLocation<'m>, Vec<DependenceKind<'m>>>,
+
+    /// The function itself
+    pub(crate) function: &'m Function,
+}
+
+/// A `DependenceKind` describes why one instruction depends on another, in a
+/// `DataDependenceGraph` or `ProgramDependenceGraph`
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum DependenceKind<'m> {
+    /// The instruction uses the SSA value with the given `Name`, which is the
+    /// result of the other instruction
+    Data(&'m Name),
+    /// The instruction may read memory written by the other instruction
+    Memory,
+    /// Whether the instruction executes depends on the branch taken by the
+    /// other instruction, which is a terminator. See
+    /// [`ControlDependenceGraph`](struct.ControlDependenceGraph.html).
+    Control,
+}
+
+/// Options for building a `DataDependenceGraph` or `ProgramDependenceGraph`.
+///
+/// More options may be added in the future, so start from
+/// `DependenceOptions::default()` and set the options you need, e.g.
+/// `DependenceOptions::default().with_memory(false)`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[non_exhaustive]
+pub struct DependenceOptions {
+    /// Whether to include `DependenceKind::Memory` dependences. If `false`,
+    /// the only data dependences are on SSA values. Defaults to `true`.
+    pub memory: bool,
+}
+
+impl Default for DependenceOptions {
+    fn default() -> Self {
+        Self { memory: true }
+    }
+}
+
+impl DependenceOptions {
+    /// Set whether to include `DependenceKind::Memory` dependences; see
+    /// `DependenceOptions.memory`
+    pub fn with_memory(self, memory: bool) -> Self {
+        Self { memory, ..self }
+    }
+}
+
+impl<'m> fmt::Display for DependenceKind<'m> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DependenceKind::Data(name) => write!(f, "{}", name),
+            DependenceKind::Memory => write!(f, "memory"),
+            DependenceKind::Control => write!(f, "control"),
+        }
+    }
+}
+
+impl<'m> DataDependenceGraph<'m> {
+    pub(crate) fn new(
+        cfg: &ControlFlowGraph<'m>,
+        def_use: &DefUseInfo<'m>,
+        options: DependenceOptions,
+    ) -> Self {
+        let function = cfg.function;
+        let mut graph: DiGraphMap<InstructionLocation<'m>, Vec<DependenceKind<'m>>> =
+            DiGraphMap::new();
+        let mut add_edge = |from, to, kind| match graph.edge_weight_mut(from, to) {
+            Some(kinds) => kinds.push(kind),
+            None => {
+                graph.add_edge(from, to, vec![kind]);
+            }
+        };
+
+        // SSA dependences
+        for (value, def) in def_use.definitions() {
+            if let Definition::Instruction(def) = def {
+                for use_ in def_use.uses(value) {
+                    add_edge(use_.location, def, DependenceKind::Data(value));
+                }
+            }
+        }
+
+        // memory dependences, from each instruction which may read memory to
+        // each write which may reach it
+        if options.memory {
+            let writes = cfg.solve_dataflow(&ReachingWrites);
+            for block in cfg.reachable_blocks() {
+                let bb = function
+                    .get_bb_by_name(block)
+                    .expect("reachable block should exist");
+                let mut reaching = writes
+                    .fact_in(block)
+                    .cloned()
+                    .expect("reachable block should have a fact");
+                for (index, inst) in instructions(bb) {
+                    let location = InstructionLocation { block, index };
+                    if may_read_memory(inst) {
+                        for &write in &reaching {
+                            add_edge(location, write, DependenceKind::Memory);
+                        }
+                    }
+                    if may_write_memory(inst) {
+                        reaching.insert(location);
+                    }
+                }
+            }
+        }
+
+        for bb in &function.basic_blocks {
+            for (index, _) in instructions(bb) {
+                graph.add_node(InstructionLocation {
+                    block: &bb.name,
+                    index,
+                });
+            }
+        }
+
+        Self { graph, function }
+    }
+
+    /// Get the instructions which the instruction at the given location has an
+    /// immediate data dependence on, along with the kind of each dependence.
+    ///
+    /// An instruction with several kinds of dependence on `location` (e.g., it
+    /// uses two of its results) appears once for each kind.
+    pub fn get_data_dependencies<'s>(
+        &'s self,
+        location: InstructionLocation<'m>,
+    ) -> impl Iterator<Item = (InstructionLocation<'m>, DependenceKind<'m>)> + 's {
+        edges_with_kind(&self.graph, location, Direction::Outgoing)
+    }
+
+    /// Get the instructions which have an immediate data dependence on the
+    /// instruction at the given location, along with the kind of each
+    /// dependence. See notes on `get_data_dependencies()`.
+    pub fn get_data_dependents<'s>(
+        &'s self,
+        location: InstructionLocation<'m>,
+    ) -> impl Iterator<Item = (InstructionLocation<'m>, DependenceKind<'m>)> + 's {
+        edges_with_kind(&self.graph, location, Direction::Incoming)
+    }
+
+    /// Render the data dependence graph in the Graphviz DOT format, with the
+    /// given options.
+    ///
+    /// An edge from X to Y indicates that instruction X has an immediate data
+    /// dependence on instruction Y.
+    pub fn to_dot(&self, options: &DotOptions<'m>) -> String {
+        dot_to_string(|w| self.write_dot(w, options))
+    }
+
+    /// Write the data dependence graph in the Graphviz DOT format to `w`, with
+    /// the given options
+    pub fn write_dot(&self, w: &mut impl Write, options: &DotOptions<'m>) -> io::Result<()> {
+        write_dependence_dot(w, &self.graph, self.function, options)
+    }
+}
+
+/// Iterate over the edges of `graph` in the given `direction` from `location`,
+/// once for each kind
+pub(crate) fn edges_with_kind<'m, 's>(
+    graph: &'s DiGraphMap<InstructionLocation<'m>, Vec<DependenceKind<'m>>>,
+    location: InstructionLocation<'m>,
+    direction: Direction,
+) -> impl Iterator<Item = (InstructionLocation<'m>, DependenceKind<'m>)> + 's {
+    graph
+        .edges_directed(location, direction)
+        .flat_map(move |(from, to, kinds)| {
+            let other = if direction == Direction::Outgoing {
+                to
+            } else {
+                from
+            };
+            kinds.iter().map(move |kind| (other, *kind))
+        })
+}
+
+/// Write a dependence graph (a `DataDependenceGraph` or
+/// `ProgramDependenceGraph`) in the Graphviz DOT format to `w`
+pub(crate) fn write_dependence_dot<'m>(
+    w: &mut impl Write,
+    graph: &DiGraphMap<InstructionLocation<'m>, Vec<DependenceKind<'m>>>,
+    function: &'m Function,
+    options: &DotOptions<'m>,
+) -> io::Result<()> {
+    write_digraph(
+        w,
+        &function.name,
+        "shape=box",
+        graph.nodes().map(|location| {
+            let id = location.to_string();
+            let mut label = escape(&id);
+            if options.instructions {
+                if let Some(inst) = location.get(function) {
+                    label.push_str(": ");
+                    label.push_str(&escape(&inst.to_string()));
+                }
+            }
+            let highlighted = options.highlight_instructions.contains(&location);
+            (id, label, highlighted)
+        }),
+        graph.all_edges().map(|(from, to, kinds)| {
+            let label = if options.edge_kinds && !kinds.is_empty() {
+                Some(
+                    kinds
+                        .iter()
+                        .map(|kind| kind.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                )
+            } else {
+                None
+            };
+            (from.to_string(), to.to_string(), label)
+        }),
+    )
+}
+
+/// Iterate over the instructions and terminator of the given block, with their
+/// indices as in `InstructionLocation`
+pub(crate) fn instructions(
+    bb: &BasicBlock,
+) -> impl Iterator<Item = (usize, Either<&Instruction, &Terminator>)> {
+    bb.instrs
+        .iter()
+        .map(Either::Left)
+        .chain(std::iter::once(Either::Right(&bb.term)))
+        .enumerate()
+}
+
+/// The `DataflowAnalysis` computing which writes to memory may reach each
+/// point, without killing any (since all memory accesses may alias)
+struct ReachingWrites;
+
+impl<'m> DataflowAnalysis<'m> for ReachingWrites {
+    type Fact = HashSet<InstructionLocation<'m>>;
+    const DIRECTION: DataflowDirection = DataflowDirection::Forward;
+
+    fn bottom(&self) -> Self::Fact {
+        HashSet::new()
+    }
+
+    fn join(&self, fact: &mut Self::Fact, other: &Self::Fact) {
+        fact.extend(other.iter().copied());
+    }
+
+    fn transfer_block(&self, block: &'m BasicBlock, fact: &mut Self::Fact) {
+        for (index, inst) in instructions(block) {
+            if may_write_memory(inst) {
+                fact.insert(InstructionLocation {
+                    block: &block.name,
+                    index,
+                });
+            }
+        }
+    }
+}
+
+fn may_read_memory(inst: Either<&Instruction, &Terminator>) -> bool {
+    match inst {
+        Either::Left(inst) => matches!(
+            inst,
+            Instruction::Load(_)
+                | Instruction::AtomicRMW(_)
+                | Instruction::CmpXchg(_)
+                | Instruction::Fence(_)
+                | Instruction::Call(_)
+                | Instruction::VAArg(_)
+        ),
+        Either::Right(term) => is_call_terminator(term),
+    }
+}
+
+fn may_write_memory(inst: Either<&Instruction, &Terminator>) -> bool {
+    match inst {
+        Either::Left(inst) => match inst {
+            // a volatile load may have side effects, e.g. on memory-mapped
+            // I/O, so we treat it as a write too
+            Instruction::Load(load) => load.volatile,
+            _ => matches!(
+                inst,
+                Instruction::Store(_)
+                    | Instruction::AtomicRMW(_)
+                    | Instruction::CmpXchg(_)
+                    | Instruction::Fence(_)
+                    | Instruction::Call(_)
+                    | Instruction::VAArg(_)
+            ),
+        },
+        Either::Right(term) => is_call_terminator(term),
+    }
+}
+
+fn is_call_terminator(term: &Terminator) -> bool {
+    match term {
+        Terminator::Invoke(_) => true,
+        #[cfg(not(feature = "llvm-8"))]
+        Terminator::CallBr(_) => true,
+        _ => false,
+    }
+}
@@ -0,0 +1,237 @@
+use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
+use llvm_ir::{BasicBlock, Instruction, Name, Terminator};
+use std::collections::{BTreeSet, HashMap};
+
+/// The direction in which facts flow in a `DataflowAnalysis`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum DataflowDirection {
+    /// Facts flow from the entry block towards `CFGNode::Return`, e.g.
+    /// reaching definitions
+    Forward,
+    /// Facts flow from `CFGNode::Return` towards the entry block, e.g.
+    /// liveness
+    Backward,
+}
+
+/// A monotone dataflow problem, which can be solved over a `ControlFlowGraph`
+/// with `ControlFlowGraph::solve_dataflow()`.
+///
+/// The facts form a lattice with the given `bottom()` and `join()`. The
+/// transfer functions must be monotone, and the lattice must have no infinite
+/// ascending chains, or the solver may not terminate.
+///
+/// Only `bottom()` and `join()` are required; by default all transfer
+/// functions are the identity. Most analyses override
+/// `transfer_instruction()` and `transfer_terminator()`, or for more control,
+/// `transfer_block()`.
+pub trait DataflowAnalysis<'m> {
+    /// The type of the facts computed by the analysis
+    type Fact: Clone + PartialEq;
+
+    /// The direction in which facts flow
+    const DIRECTION: DataflowDirection;
+
+    /// The least element of the lattice. All facts start out as `bottom()`,
+    /// except at the boundary; see `boundary()`.
+    fn bottom(&self) -> Self::Fact;
+
+    /// Join `other` into `fact`, i.e., set `fact` to the least upper bound of
+    /// the two facts. Used where control flow merges.
+    fn join(&self, fact: &mut Self::Fact, other: &Self::Fact);
+
+    /// The fact on entry to the function (for `Forward` analyses) or at
+    /// `CFGNode::Return` and `CFGNode::Unwind` (for `Backward` analyses).
+    ///
+    /// Defaults to `bottom()`.
+    fn boundary(&self) -> Self::Fact {
+        self.bottom()
+    }
+
+    /// Apply the effect of the given instruction to `fact`.
+    ///
+    /// Defaults to the identity.
+    fn transfer_instruction(&self, _inst: &'m Instruction, _fact: &mut Self::Fact) {}
+
+    /// Apply the effect of the given terminator to `fact`.
+    ///
+    /// Defaults to the identity.
+    fn transfer_terminator(&self, _term: &'m Terminator, _fact: &mut Self::Fact) {}
+
+    /// Apply the effect of the given basic block to `fact`.
+    ///
+    /// For `Forward` analyses, `fact` is initially the fact at the start of the
+    /// block, and should become the fact at the end of the block; for
+    /// `Backward` analyses, the reverse.
+    ///
+    /// Defaults to applying `transfer_instruction()` to each instruction and
+    /// `transfer_terminator()` to the terminator, in the order given by the
+    /// direction of the analysis.
+    fn transfer_block(&self, block: &'m BasicBlock, fact: &mut Self::Fact) {
+        match Self::DIRECTION {
+            DataflowDirection::Forward => {
+                for inst in &block.instrs {
+                    self.transfer_instruction(inst, fact);
+                }
+                self.transfer_terminator(&block.term, fact);
+            }
+            DataflowDirection::Backward => {
+                self.transfer_terminator(&block.term, fact);
+                for inst in block.instrs.iter().rev() {
+                    self.transfer_instruction(inst, fact);
+                }
+            }
+        }
+    }
+
+    /// Apply the effect of the CFG edge from the block `from` to `to` to
+    /// `fact`, before it is joined with the facts from other edges.
+    ///
+    /// For `Forward` analyses, `fact` is the fact at the end of `from`; for
+    /// `Backward` analyses, it is the fact at the start of `to`. This is
+    /// useful, e.g., for attributing `Phi` operands to the incoming edge.
+    ///
+    /// Defaults to the identity.
+    fn transfer_edge(&self, _from: &'m Name, _to: CFGNode<'m>, _fact: &mut Self::Fact) {}
+}
+
+/// The solution of a `DataflowAnalysis` over a `ControlFlowGraph`, giving the
+/// fact at the start and end of each reachable basic block.
+///
+/// "In" and "out" always refer to the start and end of a block in terms of
+/// control flow, regardless of the direction of the analysis.
+///
+/// To construct a `DataflowResult`, use
+/// [`ControlFlowGraph::solve_dataflow()`](struct.ControlFlowGraph.html#method.solve_dataflow).
+pub struct DataflowResult<'m, F> {
+    /// Map from each reachable `CFGNode` to the fact at its start
+    ins: HashMap<CFGNode<'m>, F>,
+    /// Map from each reachable `CFGNode` to the fact at its end. For
+    /// `CFGNode::Return` and `CFGNode::Unwind`, this is the same as the fact
+    /// at its start.
+    outs: HashMap<CFGNode<'m>, F>,
+}
+
+impl<'m, F> DataflowResult<'m, F> {
+    /// Get the fact at the start of the basic block with the given `Name`.
+    ///
+    /// Returns `None` if the block is unreachable.
+    pub fn fact_in(&self, block: &'m Name) -> Option<&F> {
+        self.ins.get(&CFGNode::Block(block))
+    }
+
+    /// Get the fact at the end of the basic block with the given `Name`.
+    ///
+    /// Returns `None` if the block is unreachable.
+    pub fn fact_out(&self, block: &'m Name) -> Option<&F> {
+        self.outs.get(&CFGNode::Block(block))
+    }
+
+    /// Get the fact at the special `Return` node, i.e., when the function
+    /// returns (or, with `ExceptionalFlow::MergeWithReturn`, unwinds).
+    ///
+    /// Returns `None` if the function can't return.
+    pub fn fact_at_return(&self) -> Option<&F> {
+        self.ins.get(&CFGNode::Return)
+    }
+
+    /// Get the fact at the special `Unwind` node, i.e., when an exception
+    /// propagates out of the function.
+    ///
+    /// This is always `None` unless the `ControlFlowGraph` was built with
+    /// `ExceptionalFlow::SeparateUnwind`.
+    pub fn fact_at_unwind(&self) -> Option<&F> {
+        self.ins.get(&CFGNode::Unwind)
+    }
+}
+
+/// Solve the given `DataflowAnalysis` over `cfg` with a worklist algorithm,
+/// visiting nodes in reverse postorder (for `Forward` analyses) or postorder
+/// (for `Backward` analyses)
+pub(crate) fn solve<'m, A: DataflowAnalysis<'m>>(
+    cfg: &ControlFlowGraph<'m>,
+    analysis: &A,
+) -> DataflowResult<'m, A::Fact> {
+    let blocks: HashMap<&'m Name, &'m BasicBlock> = cfg
+        .function
+        .basic_blocks
+        .iter()
+        .map(|bb| (&bb.name, bb))
+        .collect();
+    let nodes_by_rpo: Vec<CFGNode<'m>> = cfg.reverse_postorder().collect();
+
+    let mut ins: HashMap<CFGNode<'m>, A::Fact> = nodes_by_rpo
+        .iter()
+        .map(|&node| (node, analysis.bottom()))
+        .collect();
+    let mut outs = ins.clone();
+
+    // The worklist holds rpo numbers, so that we can always take the earliest
+    // node in reverse postorder (for `Forward`) or postorder (for `Backward`)
+    let mut worklist: BTreeSet<usize> = (0..nodes_by_rpo.len()).collect();
+    loop {
+        let next = match A::DIRECTION {
+            DataflowDirection::Forward => worklist.iter().next(),
+            DataflowDirection::Backward => worklist.iter().next_back(),
+        };
+        let rpo_number = match next {
+            Some(&rpo_number) => rpo_number,
+            None => break,
+        };
+        worklist.remove(&rpo_number);
+        let node = nodes_by_rpo[rpo_number];
+
+        match A::DIRECTION {
+            DataflowDirection::Forward => {
+                let mut fact = if node == cfg.entry_node {
+                    analysis.boundary()
+                } else {
+                    analysis.bottom()
+                };
+                for pred in cfg.preds_of_cfgnode(node) {
+                    // unreachable predecessors have no facts, and contribute
+                    // nothing
+                    if let Some(pred_fact) = outs.get(&CFGNode::Block(pred)) {
+                        let mut pred_fact = pred_fact.clone();
+                        analysis.transfer_edge(pred, node, &mut pred_fact);
+                        analysis.join(&mut fact, &pred_fact);
+                    }
+                }
+                ins.insert(node, fact.clone());
+                if let CFGNode::Block(block) = node {
+                    analysis.transfer_block(blocks[block], &mut fact);
+                }
+                if outs[&node] != fact {
+                    outs.insert(node, fact);
+                    worklist.extend(cfg.succs_as_nodes(node).map(|succ| cfg.rpo_numbers[&succ]));
+                }
+            }
+            DataflowDirection::Backward => {
+                let mut fact = match node {
+                    CFGNode::Block(block) => {
+                        let mut fact = analysis.bottom();
+                        for succ in cfg.succs(block) {
+                            let mut succ_fact = ins[&succ].clone();
+                            analysis.transfer_edge(block, succ, &mut succ_fact);
+                            analysis.join(&mut fact, &succ_fact);
+                        }
+                        fact
+                    }
+                    CFGNode::Return | CFGNode::Unwind => analysis.boundary(),
+                };
+                outs.insert(node, fact.clone());
+                if let CFGNode::Block(block) = node {
+                    analysis.transfer_block(blocks[block], &mut fact);
+                }
+                if ins[&node] != fact {
+                    ins.insert(node, fact);
+                    worklist.extend(
+                        cfg.preds_as_nodes(node)
+                            .filter_map(|pred| cfg.rpo_numbers.get(&pred).copied()),
+                    );
+                }
+            }
+        }
+    }
+
+    DataflowResult { ins, outs }
+}
@@ -0,0 +1,96 @@
+use crate::address_taken::AddressTakenFunctions;
+use crate::call_graph::CallGraph;
+use crate::error::{Error, Result};
+use crate::ModuleFunctions;
+use llvm_ir::module::Linkage;
+use std::collections::HashSet;
+
+/// Which functions a `DeadFunctions` analysis treats as entry points, i.e., as
+/// live regardless of whether anything calls them
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EntryPoints<'a> {
+    /// The functions with the given names
+    Named(&'a [&'a str]),
+    /// All the functions which are visible outside of their `Module`, i.e.,
+    /// which don't have `Internal` or `Private` linkage
+    ExternallyVisible,
+}
+
+/// The internal and private functions in the analyzed `Module`(s) which can
+/// never be executed: they can't be (transitively) called from any of the
+/// given `EntryPoints`, and their address is never taken.
+///
+/// Functions whose address is taken are conservatively assumed to be live,
+/// along with everything they may call, wherever the address is taken.
+/// Functions which are visible outside of their `Module` are never reported
+/// as dead, even if they are not entry points.
+///
+/// To construct a `DeadFunctions`, use
+/// [`ModuleAnalysis`](struct.ModuleAnalysis.html) or
+/// [`CrossModuleAnalysis`](struct.CrossModuleAnalysis.html).
+pub struct DeadFunctions<'m> {
+    /// Names of the dead functions, sorted
+    dead: Vec<&'m str>,
+}
+
+impl<'m> DeadFunctions<'m> {
+    pub(crate) fn new<'a>(
+        modules: impl IntoIterator<Item = ModuleFunctions<'m, 'a>>,
+        call_graph: &CallGraph<'m>,
+        address_taken: &AddressTakenFunctions<'m>,
+        entry_points: EntryPoints,
+    ) -> Result<Self>
+    where
+        'm: 'a,
+    {
+        let functions: Vec<_> = modules
+            .into_iter()
+            .flat_map(|m| m.functions.iter().copied())
+            .collect();
+        let is_local = |linkage: &Linkage| matches!(linkage, Linkage::Internal | Linkage::Private);
+        let roots: Vec<&'m str> = match entry_points {
+            EntryPoints::Named(names) => names
+                .iter()
+                .map(|name| {
+                    functions
+                        .iter()
+                        .find(|f| f.name == *name)
+                        .map(|f| f.name.as_str())
+                        .ok_or_else(|| Error::FunctionNotFound {
+                            name: name.to_string(),
+                        })
+                })
+                .collect::<Result<_>>()?,
+            EntryPoints::ExternallyVisible => functions
+                .iter()
+                .filter(|f| !is_local(&f.linkage))
+                .map(|f| f.name.as_str())
+                .collect(),
+        };
+        // declarations can't call anything, and may not even be in the call
+        // graph, so only defined functions are roots
+        let address_taken_roots = functions
+            .iter()
+            .map(|f| f.name.as_str())
+            .filter(|func_name| address_taken.is_address_taken(func_name));
+        let live: HashSet<&'m str> =
+            call_graph.reachable_from(roots.into_iter().chain(address_taken_roots))?;
+        let mut dead: Vec<&'m str> = functions
+            .iter()
+            .filter(|f| is_local(&f.linkage) && !live.contains(f.name.as_str()))
+            .map(|f| f.name.as_str())
+            .collect();
+        dead.sort_unstable();
+        Ok(Self { dead })
+    }
+
+    /// Is the function with the given name dead?
+    pub fn is_dead(&self, func_name: &str) -> bool {
+        self.dead.binary_search(&func_name).is_ok()
+    }
+
+    /// Iterate over the names of all the dead functions, in sorted order
+    pub fn dead_functions<'s>(&'s self) -> impl Iterator<Item = &'m str> + 's {
+        self.dead.iter().copied()
+    }
+}
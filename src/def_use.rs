@@ -0,0 +1,182 @@
+use crate::operands::{instruction_operands, terminator_operands};
+use either::Either;
+use llvm_ir::{Function, Instruction, Name, Operand, Terminator};
+use std::collections::HashMap;
+use std::fmt;
+
+/// The location of an instruction or terminator in a function
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct InstructionLocation<'m> {
+    /// Name of the basic block containing the instruction
+    pub block: &'m Name,
+    /// Index of the instruction in the block's instructions. For the
+    /// terminator, this is the number of instructions in the block, as the
+    /// terminator comes after all of them.
+    pub index: usize,
+}
+
+impl<'m> InstructionLocation<'m> {
+    /// Get the instruction or terminator at this location in the given
+    /// function.
+    ///
+    /// Returns `None` if the function has no such block, or the block has no
+    /// such instruction.
+    pub fn get(&self, function: &'m Function) -> Option<Either<&'m Instruction, &'m Terminator>> {
+        let bb = function.get_bb_by_name(self.block)?;
+        if self.index == bb.instrs.len() {
+            Some(Either::Right(&bb.term))
+        } else {
+            bb.instrs.get(self.index).map(Either::Left)
+        }
+    }
+}
+
+impl<'m> fmt::Display for InstructionLocation<'m> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}[{}]", self.block, self.index)
+    }
+}
+
+/// Where an SSA value is defined
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum Definition<'m> {
+    /// The function parameter with the given index
+    Parameter(usize),
+    /// The result of the instruction (or terminator, e.g. `Invoke`) at the
+    /// given location
+    Instruction(InstructionLocation<'m>),
+}
+
+/// A use of an SSA value as an operand
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct Use<'m> {
+    /// The instruction or terminator using the value
+    pub location: InstructionLocation<'m>,
+    /// If the use is by a `Phi`, the predecessor block which the value comes
+    /// from. The value is only used when control comes from that block.
+    pub phi_incoming_block: Option<&'m Name>,
+}
+
+/// Def-use and use-def chains for the SSA values (instruction results and
+/// function parameters) in a particular function.
+///
+/// Only uses as operands of instructions and terminators are included, not
+/// e.g. uses of basic block names as branch targets.
+///
+/// To construct a `DefUseInfo`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct DefUseInfo<'m> {
+    /// Map from each value to where it is defined
+    definitions: HashMap<&'m Name, Definition<'m>>,
+
+    /// Map from each value to its uses, in the order they appear in the
+    /// function
+    uses: HashMap<&'m Name, Vec<Use<'m>>>,
+
+    /// Map from each instruction to the values it uses, in operand order.
+    /// A value used several times by an instruction appears several times.
+    used_values: HashMap<InstructionLocation<'m>, Vec<&'m Name>>,
+}
+
+impl<'m> DefUseInfo<'m> {
+    pub(crate) fn new(function: &'m Function) -> Self {
+        let mut definitions = HashMap::new();
+        let mut uses: HashMap<&'m Name, Vec<Use<'m>>> = HashMap::new();
+        let mut used_values: HashMap<InstructionLocation<'m>, Vec<&'m Name>> = HashMap::new();
+
+        for (index, param) in function.parameters.iter().enumerate() {
+            definitions.insert(&param.name, Definition::Parameter(index));
+        }
+
+        let mut add_use = |value: &'m Operand, location, phi_incoming_block| {
+            if let Operand::LocalOperand { name, .. } = value {
+                uses.entry(name).or_default().push(Use {
+                    location,
+                    phi_incoming_block,
+                });
+                used_values.entry(location).or_default().push(name);
+            }
+        };
+
+        for bb in &function.basic_blocks {
+            for (index, inst) in bb.instrs.iter().enumerate() {
+                let location = InstructionLocation {
+                    block: &bb.name,
+                    index,
+                };
+                if let Some(result) = inst.try_get_result() {
+                    definitions.insert(result, Definition::Instruction(location));
+                }
+                match inst {
+                    Instruction::Phi(phi) => {
+                        for (value, pred) in &phi.incoming_values {
+                            add_use(value, location, Some(pred));
+                        }
+                    }
+                    _ => {
+                        for operand in instruction_operands(inst) {
+                            add_use(operand, location, None);
+                        }
+                    }
+                }
+            }
+            let location = InstructionLocation {
+                block: &bb.name,
+                index: bb.instrs.len(),
+            };
+            if let Some(result) = bb.term.try_get_result() {
+                definitions.insert(result, Definition::Instruction(location));
+            }
+            for operand in terminator_operands(&bb.term) {
+                add_use(operand, location, None);
+            }
+        }
+
+        Self {
+            definitions,
+            uses,
+            used_values,
+        }
+    }
+
+    /// Get where the value with the given `Name` is defined.
+    ///
+    /// Returns `None` if there is no such value in the function.
+    pub fn definition(&self, value: &Name) -> Option<Definition<'m>> {
+        self.definitions.get(value).copied()
+    }
+
+    /// Iterate over the uses of the value with the given `Name`, in the order
+    /// they appear in the function
+    pub fn uses<'s>(&'s self, value: &Name) -> impl Iterator<Item = Use<'m>> + 's {
+        self.uses.get(value).into_iter().flatten().copied()
+    }
+
+    /// Does the value with the given `Name` have any uses?
+    pub fn is_used(&self, value: &Name) -> bool {
+        self.uses.contains_key(value)
+    }
+
+    /// Iterate over the values used by the instruction or terminator at the
+    /// given location, in operand order. A value used several times by the
+    /// instruction appears several times.
+    ///
+    /// To find where each value is defined, use `definition()`.
+    pub fn used_values<'s>(
+        &'s self,
+        location: InstructionLocation<'m>,
+    ) -> impl Iterator<Item = &'m Name> + 's {
+        self.used_values
+            .get(&location)
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+
+    /// Iterate over all the values defined in the function, and where each is
+    /// defined, in no particular order
+    pub fn definitions<'s>(&'s self) -> impl Iterator<Item = (&'m Name, Definition<'m>)> + 's {
+        self.definitions.iter().map(|(&name, &def)| (name, def))
+    }
+}
@@ -1,9 +1,12 @@
 use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
-use llvm_ir::Name;
-use petgraph::prelude::{Dfs, DiGraphMap, Direction};
+use crate::dot::{cfgnode_dot_node, dot_to_string, write_digraph, DotOptions};
+use crate::error::{Error, Result};
+use llvm_ir::{Function, Name};
+use petgraph::prelude::{DfsPostOrder, DiGraphMap, Direction};
 use petgraph::visit::Walker;
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
 
 /// The dominator tree for a particular function.
 ///
@@ -23,6 +26,17 @@ pub struct DominatorTree<'m> {
 
     /// Entry node for the function
     pub(crate) entry_node: CFGNode<'m>,
+
+    /// Map from `CFGNode` to its dominance frontier.
+    ///
+    /// Unreachable blocks won't be in this map.
+    frontiers: HashMap<CFGNode<'m>, Vec<CFGNode<'m>>>,
+
+    /// Numbering of the tree, for fast dominance queries
+    numbering: TreeNumbering<'m>,
+
+    /// The function itself
+    function: &'m Function,
 }
 
 /// The postdominator tree for a particular function.
@@ -40,6 +54,120 @@ pub struct PostDominatorTree<'m> {
     ///   - Of the blocks that strictly postdominate bbY, bbX is the closest to bbY
     ///     (farthest from exit) along paths from bbY to the function exit
     pub(crate) graph: DiGraphMap<CFGNode<'m>, ()>,
+
+    /// Map from `CFGNode` to its postdominance frontier.
+    ///
+    /// Blocks from which the function exit is unreachable won't be in this map.
+    frontiers: HashMap<CFGNode<'m>, Vec<CFGNode<'m>>>,
+
+    /// Numbering of the tree, for fast postdominance queries
+    numbering: TreeNumbering<'m>,
+
+    /// The function itself
+    function: &'m Function,
+}
+
+/// A numbering of the nodes of a dominator or postdominator tree, which allows
+/// answering ancestor queries in constant time, and nearest-common-ancestor
+/// queries in logarithmic time.
+struct TreeNumbering<'m> {
+    /// Map from `CFGNode` to its preorder number in the tree.
+    ///
+    /// Nodes not in the tree (e.g., unreachable blocks) won't be in this map.
+    preorder: HashMap<CFGNode<'m>, usize>,
+
+    /// The nodes of the tree, indexed by preorder number
+    nodes: Vec<CFGNode<'m>>,
+
+    /// For each node (indexed by preorder number), the largest preorder number
+    /// of any node in its subtree. Since the subtree of a node is numbered
+    /// contiguously, X is an ancestor of Y if and only if
+    /// `preorder[X] <= preorder[Y] <= subtree_end[X]`.
+    subtree_end: Vec<usize>,
+
+    /// `ancestors[k][i]` is the preorder number of the `2^k`-th ancestor of the
+    /// node with preorder number `i`, or of the root if there is no such
+    /// ancestor
+    ancestors: Vec<Vec<usize>>,
+}
+
+impl<'m> TreeNumbering<'m> {
+    fn new(tree: &DiGraphMap<CFGNode<'m>, ()>, root: CFGNode<'m>) -> Self {
+        let mut preorder = HashMap::new();
+        let mut nodes = vec![];
+        let mut parents = vec![];
+        // the root is always numbered 0, even if it has no edges (and so may
+        // not be a node of `tree`, e.g. in a function with a single block).
+        // The subtree of each node is numbered contiguously, because we
+        // finish a node's entire subtree before popping anything that was
+        // below it on the stack
+        let mut stack = vec![(root, 0)];
+        while let Some((node, parent)) = stack.pop() {
+            let num = nodes.len();
+            preorder.insert(node, num);
+            nodes.push(node);
+            parents.push(parent);
+            stack.extend(
+                tree.neighbors_directed(node, Direction::Outgoing)
+                    .map(|child| (child, num)),
+            );
+        }
+
+        let mut subtree_end: Vec<usize> = (0..nodes.len()).collect();
+        for num in (1..nodes.len()).rev() {
+            let parent = parents[num];
+            subtree_end[parent] = subtree_end[parent].max(subtree_end[num]);
+        }
+
+        let mut ancestors = vec![parents];
+        let mut span = 1;
+        while span < nodes.len() {
+            let prev = ancestors.last().expect("ancestors should be nonempty");
+            let next = prev.iter().map(|&anc| prev[anc]).collect();
+            ancestors.push(next);
+            span *= 2;
+        }
+
+        Self {
+            preorder,
+            nodes,
+            subtree_end,
+            ancestors,
+        }
+    }
+
+    /// Is `a` an ancestor of `b` (or equal to `b`)?
+    fn is_ancestor(&self, a: CFGNode<'m>, b: CFGNode<'m>) -> bool {
+        if a == b {
+            return true;
+        }
+        match (self.preorder.get(&a), self.preorder.get(&b)) {
+            (Some(&a), Some(&b)) => self.is_ancestor_num(a, b),
+            _ => false,
+        }
+    }
+
+    fn is_ancestor_num(&self, a: usize, b: usize) -> bool {
+        a <= b && b <= self.subtree_end[a]
+    }
+
+    /// Get the nearest common ancestor of `a` and `b`, or `None` if either of
+    /// them is not in the tree
+    fn nearest_common_ancestor(&self, a: CFGNode<'m>, b: CFGNode<'m>) -> Option<CFGNode<'m>> {
+        let mut a = *self.preorder.get(&a)?;
+        let b = *self.preorder.get(&b)?;
+        if self.is_ancestor_num(a, b) {
+            return Some(self.nodes[a]);
+        }
+        // climb from `a` as far as possible while staying below the nearest
+        // common ancestor; its parent is then the nearest common ancestor
+        for level in self.ancestors.iter().rev() {
+            if !self.is_ancestor_num(level[a], b) {
+                a = level[a];
+            }
+        }
+        Some(self.nodes[self.ancestors[0][a]])
+    }
 }
 
 /// Contains state used when constructing the `DominatorTree` or `PostDominatorTree`
@@ -47,12 +175,6 @@ struct DomTreeBuilder<'m, 'a> {
     /// The `ControlFlowGraph` we're working from
     cfg: &'a ControlFlowGraph<'m>,
 
-    /// Map from `CFGNode` to its rpo number.
-    ///
-    /// Unreachable blocks won't be in this map; all reachable blocks will have
-    /// positive rpo numbers.
-    rpo_numbers: HashMap<CFGNode<'m>, usize>,
-
     /// Map from `CFGNode` to the current estimate for its immediate dominator
     /// (the entry node maps to `None`).
     ///
@@ -67,10 +189,6 @@ impl<'m, 'a> DomTreeBuilder<'m, 'a> {
     fn new(cfg: &'a ControlFlowGraph<'m>) -> Self {
         Self {
             cfg,
-            rpo_numbers: Dfs::new(&cfg.graph, cfg.entry_node)
-                .iter(&cfg.graph)
-                .zip(1..)
-                .collect(),
             idoms: HashMap::new(),
         }
     }
@@ -82,15 +200,17 @@ impl<'m, 'a> DomTreeBuilder<'m, 'a> {
         // according to comments in Cranelift's code.
 
         // first compute initial (preliminary) estimates for the immediate
-        // dominator of each block
-        for block in Dfs::new(&self.cfg.graph, self.cfg.entry_node).iter(&self.cfg.graph) {
+        // dominator of each block. In reverse postorder, each block (other than
+        // the entry) comes after at least one of its predecessors.
+        let cfg = self.cfg;
+        for block in cfg.reverse_postorder() {
             self.idoms.insert(block, self.compute_idom(block));
         }
 
         let mut changed = true;
         while changed {
             changed = false;
-            for block in Dfs::new(&self.cfg.graph, self.cfg.entry_node).iter(&self.cfg.graph) {
+            for block in cfg.reverse_postorder() {
                 let idom = self.compute_idom(block);
                 let prev_idom = self
                     .idoms
@@ -141,7 +261,7 @@ impl<'m, 'a> DomTreeBuilder<'m, 'a> {
     /// Both nodes are assumed to be reachable.
     fn common_dominator(&self, mut node_a: CFGNode<'m>, mut node_b: CFGNode<'m>) -> CFGNode<'m> {
         loop {
-            match self.rpo_numbers[&node_a].cmp(&self.rpo_numbers[&node_b]) {
+            match self.cfg.rpo_numbers[&node_a].cmp(&self.cfg.rpo_numbers[&node_b]) {
                 Ordering::Less => {
                     node_b = self.idoms[&node_b]
                         .expect("entry node should have the smallest rpo number");
@@ -158,11 +278,71 @@ impl<'m, 'a> DomTreeBuilder<'m, 'a> {
     }
 }
 
+/// Compute the dominance frontier of every node in the dominator tree `domtree`
+/// for the given `cfg`.
+///
+/// Computing postdominance frontiers works the same way, with the reversed CFG
+/// and the postdominator tree.
+fn compute_frontiers<'m>(
+    cfg: &ControlFlowGraph<'m>,
+    domtree: &DiGraphMap<CFGNode<'m>, ()>,
+) -> HashMap<CFGNode<'m>, Vec<CFGNode<'m>>> {
+    // algorithm thanks to Cytron, Ferrante, Rosen, et al. "Efficiently Computing Static Single Assignment Form and the Control Dependence Graph"
+    // https://www.cs.utexas.edu/~pingali/CS380C/2010/papers/ssaCytron.pdf (Figure 10)
+
+    let idom = |node: CFGNode<'m>| domtree.neighbors_directed(node, Direction::Incoming).next();
+
+    let mut frontiers: HashMap<CFGNode<'m>, Vec<CFGNode<'m>>> = HashMap::new();
+    // postorder on the dominator tree, so that we visit each node only after
+    // all of its children
+    for block_x in DfsPostOrder::new(domtree, cfg.entry_node).iter(domtree) {
+        let mut frontier_of_x = vec![];
+        for block_y in cfg.succs_as_nodes(block_x) {
+            if idom(block_y) != Some(block_x) && !frontier_of_x.contains(&block_y) {
+                frontier_of_x.push(block_y);
+            }
+        }
+        for block_z in domtree.neighbors_directed(block_x, Direction::Outgoing) {
+            // we should have already computed the frontier of block_z
+            for &block_y in &frontiers[&block_z] {
+                if idom(block_y) != Some(block_x) && !frontier_of_x.contains(&block_y) {
+                    frontier_of_x.push(block_y);
+                }
+            }
+        }
+        frontiers.insert(block_x, frontier_of_x);
+    }
+    frontiers
+}
+
+/// Compute the iterated frontier of `nodes`, given the (non-iterated) `frontiers`
+fn iterated_frontier<'m>(
+    frontiers: &HashMap<CFGNode<'m>, Vec<CFGNode<'m>>>,
+    nodes: impl IntoIterator<Item = CFGNode<'m>>,
+) -> HashSet<CFGNode<'m>> {
+    let mut worklist: Vec<CFGNode<'m>> = nodes.into_iter().collect();
+    let mut result: HashSet<CFGNode<'m>> = HashSet::new();
+    while let Some(node) = worklist.pop() {
+        for &frontier_node in frontiers.get(&node).into_iter().flatten() {
+            if result.insert(frontier_node) {
+                worklist.push(frontier_node);
+            }
+        }
+    }
+    result
+}
+
 impl<'m> DominatorTree<'m> {
     pub(crate) fn new(cfg: &ControlFlowGraph<'m>) -> Self {
+        let graph = DomTreeBuilder::new(cfg).build();
+        let frontiers = compute_frontiers(cfg, &graph);
+        let numbering = TreeNumbering::new(&graph, cfg.entry_node);
         Self {
-            graph: DomTreeBuilder::new(cfg).build(),
+            graph,
             entry_node: cfg.entry_node,
+            frontiers,
+            numbering,
+            function: cfg.function,
         }
     }
 
@@ -176,19 +356,41 @@ impl<'m> DominatorTree<'m> {
     ///     path from the entry block to bbY (but bbX =/= bbY)
     ///   - Of the blocks that strictly dominate bbY, bbX is the closest to bbY
     ///     (farthest from entry) along paths from the entry block to bbY
+    ///
+    /// Panics if the dominator tree is malformed; see `try_idom()` for a
+    /// non-panicking version.
     pub fn idom(&self, block: &'m Name) -> Option<&'m Name> {
+        self.try_idom(block).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the immediate dominator of the basic block with the given `Name`.
+    /// See notes on `idom()`.
+    ///
+    /// Error if the dominator tree is malformed, e.g., if the block has more
+    /// than one immediate dominator.
+    pub fn try_idom(&self, block: &'m Name) -> Result<Option<&'m Name>> {
         let mut parents = self
             .graph
             .neighbors_directed(CFGNode::Block(block), Direction::Incoming);
-        let idom = parents.next()?;
-        if let Some(_) = parents.next() {
-            panic!("Block {:?} should have only one immediate dominator", block);
+        let idom = match parents.next() {
+            Some(idom) => idom,
+            None => return Ok(None),
+        };
+        if parents.next().is_some() {
+            return Err(Error::MalformedCFG {
+                function: self.function.name.clone(),
+                message: format!("Block {:?} should have only one immediate dominator", block),
+            });
         }
         match idom {
-            CFGNode::Block(block) => Some(block),
-            CFGNode::Return => {
-                panic!("Return node shouldn't be the immediate dominator of anything")
-            }
+            CFGNode::Block(block) => Ok(Some(block)),
+            node => Err(Error::MalformedCFG {
+                function: self.function.name.clone(),
+                message: format!(
+                    "{} node shouldn't be the immediate dominator of anything",
+                    node
+                ),
+            }),
         }
     }
 
@@ -204,17 +406,64 @@ impl<'m> DominatorTree<'m> {
     /// If the return node is unreachable (e.g., due to an infinite loop in the
     /// function), then the return node has no immediate dominator, and `None` will
     /// be returned.
+    ///
+    /// Panics if the dominator tree is malformed; see `try_idom_of_return()`
+    /// for a non-panicking version.
     pub fn idom_of_return(&self) -> Option<&'m Name> {
-        let mut parents = self
-            .graph
-            .neighbors_directed(CFGNode::Return, Direction::Incoming);
-        let idom = parents.next()?;
-        if let Some(_) = parents.next() {
-            panic!("Return node should have only one immediate dominator");
+        self.try_idom_of_return()
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the immediate dominator of `CFGNode::Return`. See notes on
+    /// `idom_of_return()`.
+    ///
+    /// Error if the dominator tree is malformed.
+    pub fn try_idom_of_return(&self) -> Result<Option<&'m Name>> {
+        self.idom_of_exit(CFGNode::Return)
+    }
+
+    /// Get the immediate dominator of `CFGNode::Unwind`.
+    ///
+    /// This is analogous to `idom_of_return()`. It will be `None` unless the
+    /// `ControlFlowGraph` was built with `ExceptionalFlow::SeparateUnwind` and
+    /// the function may unwind to its caller.
+    ///
+    /// Panics if the dominator tree is malformed; see `try_idom_of_unwind()`
+    /// for a non-panicking version.
+    pub fn idom_of_unwind(&self) -> Option<&'m Name> {
+        self.try_idom_of_unwind()
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the immediate dominator of `CFGNode::Unwind`. See notes on
+    /// `idom_of_unwind()`.
+    ///
+    /// Error if the dominator tree is malformed.
+    pub fn try_idom_of_unwind(&self) -> Result<Option<&'m Name>> {
+        self.idom_of_exit(CFGNode::Unwind)
+    }
+
+    fn idom_of_exit(&self, exit: CFGNode<'m>) -> Result<Option<&'m Name>> {
+        let mut parents = self.graph.neighbors_directed(exit, Direction::Incoming);
+        let idom = match parents.next() {
+            Some(idom) => idom,
+            None => return Ok(None),
+        };
+        if parents.next().is_some() {
+            return Err(Error::MalformedCFG {
+                function: self.function.name.clone(),
+                message: format!("{} node should have only one immediate dominator", exit),
+            });
         }
         match idom {
-            CFGNode::Block(block) => Some(block),
-            CFGNode::Return => panic!("Return node shouldn't be its own immediate dominator"),
+            CFGNode::Block(block) => Ok(Some(block)),
+            node => Err(Error::MalformedCFG {
+                function: self.function.name.clone(),
+                message: format!(
+                    "{} node shouldn't be the immediate dominator of {}",
+                    node, exit
+                ),
+            }),
         }
     }
 
@@ -232,8 +481,10 @@ impl<'m> DominatorTree<'m> {
     /// Note that every node dominates itself by definition, so if
     /// `node_a == node_b`, this returns `true`.
     /// See also `strictly_dominates()`
+    ///
+    /// This query takes constant time.
     pub fn dominates(&self, node_a: CFGNode<'m>, node_b: CFGNode<'m>) -> bool {
-        petgraph::algo::has_path_connecting(&self.graph, node_a, node_b, None)
+        self.numbering.is_ancestor(node_a, node_b)
     }
 
     /// Does `node_a` strictly dominate `node_b`?
@@ -244,13 +495,97 @@ impl<'m> DominatorTree<'m> {
         node_a != node_b && self.dominates(node_a, node_b)
     }
 
+    /// Get the nearest common dominator of `node_a` and `node_b`, i.e., the
+    /// node which dominates both `node_a` and `node_b` and is dominated by
+    /// every other node that dominates both of them.
+    ///
+    /// If one of the nodes dominates the other, that node is the result.
+    /// Returns `None` if either node is unreachable.
+    ///
+    /// This query takes time logarithmic in the number of blocks.
+    pub fn nearest_common_dominator(
+        &self,
+        node_a: CFGNode<'m>,
+        node_b: CFGNode<'m>,
+    ) -> Option<CFGNode<'m>> {
+        self.numbering.nearest_common_ancestor(node_a, node_b)
+    }
+
+    /// Get the dominance frontier of the basic block with the given `Name`.
+    ///
+    /// The dominance frontier of bbX is the set of nodes bbY such that bbX
+    /// dominates a predecessor of bbY, but does not strictly dominate bbY.
+    /// Informally, these are the nodes where bbX's dominance "ends".
+    ///
+    /// This will be empty for unreachable blocks.
+    pub fn dominance_frontier<'s>(
+        &'s self,
+        block: &'m Name,
+    ) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        self.frontiers
+            .get(&CFGNode::Block(block))
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+
+    /// Get the iterated dominance frontier of the given set of basic blocks.
+    ///
+    /// This is the limit of the sequence DF(S), DF(S ∪ DF(S)), ..., where DF(S)
+    /// is the union of the dominance frontiers of the blocks in S. It is,
+    /// e.g., the set of nodes which need a phi for a variable assigned in the
+    /// blocks in S (Cytron et al).
+    pub fn iterated_dominance_frontier(
+        &self,
+        blocks: impl IntoIterator<Item = &'m Name>,
+    ) -> impl Iterator<Item = CFGNode<'m>> {
+        iterated_frontier(&self.frontiers, blocks.into_iter().map(CFGNode::Block)).into_iter()
+    }
+
     /// Get the `Name` of the entry block for the function
+    ///
+    /// Panics if the entry is not a block; see `try_entry()` for a
+    /// non-panicking version.
     pub fn entry(&self) -> &'m Name {
+        self.try_entry().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the `Name` of the entry block for the function
+    ///
+    /// Error if the entry is not a block.
+    pub fn try_entry(&self) -> Result<&'m Name> {
         match self.entry_node {
-            CFGNode::Block(block) => block,
-            CFGNode::Return => panic!("Return node should not be entry"),
+            CFGNode::Block(block) => Ok(block),
+            node => Err(Error::MalformedCFG {
+                function: self.function.name.clone(),
+                message: format!("{} node should not be entry", node),
+            }),
         }
     }
+
+    /// Render the dominator tree in the Graphviz DOT format, with the given options.
+    ///
+    /// An edge from bbX to bbY indicates that bbX is the immediate dominator of
+    /// bbY.
+    pub fn to_dot(&self, options: &DotOptions<'m>) -> String {
+        dot_to_string(|w| self.write_dot(w, options))
+    }
+
+    /// Write the dominator tree in the Graphviz DOT format to `w`, with the given
+    /// options
+    pub fn write_dot(&self, w: &mut impl Write, options: &DotOptions<'m>) -> io::Result<()> {
+        write_digraph(
+            w,
+            &self.function.name,
+            "shape=box",
+            self.graph
+                .nodes()
+                .map(|node| cfgnode_dot_node(self.function, node, options)),
+            self.graph
+                .all_edges()
+                .map(|(from, to, _)| (from.to_string(), to.to_string(), None)),
+        )
+    }
 }
 
 impl<'m> PostDominatorTree<'m> {
@@ -258,8 +593,15 @@ impl<'m> PostDominatorTree<'m> {
         // The postdominator relation for `cfg` is the dominator relation on
         // the reversed `cfg` (Cytron et al, p. 477)
 
+        let reversed = cfg.reversed();
+        let graph = DomTreeBuilder::new(&reversed).build();
+        let frontiers = compute_frontiers(&reversed, &graph);
+        let numbering = TreeNumbering::new(&graph, reversed.entry_node);
         Self {
-            graph: DomTreeBuilder::new(&cfg.reversed()).build(),
+            graph,
+            frontiers,
+            numbering,
+            function: cfg.function,
         }
     }
 
@@ -277,22 +619,44 @@ impl<'m> PostDominatorTree<'m> {
     ///
     /// If the immediate postdominator is `CFGNode::Return`, that indicates that
     /// there is no single basic block that postdominates the given block.
+    /// Likewise for `CFGNode::Unwind`, which only appears if the
+    /// `ControlFlowGraph` was built with `ExceptionalFlow::SeparateUnwind`;
+    /// then, `CFGNode::Unwind` is itself immediately postdominated by
+    /// `CFGNode::Return`.
+    ///
+    /// Panics if the postdominator tree is malformed; see `try_ipostdom()` for
+    /// a non-panicking version.
     pub fn ipostdom(&self, block: &'m Name) -> Option<CFGNode<'m>> {
-        self.ipostdom_of_cfgnode(CFGNode::Block(block))
+        self.try_ipostdom(block).unwrap_or_else(|e| panic!("{}", e))
     }
 
-    /// See notes on `ipostdom()`, but in addition, this will be `None` for
+    /// Get the immediate postdominator of the basic block with the given
+    /// `Name`. See notes on `ipostdom()`.
+    ///
+    /// Error if the postdominator tree is malformed, e.g., if the block has
+    /// more than one immediate postdominator.
+    pub fn try_ipostdom(&self, block: &'m Name) -> Result<Option<CFGNode<'m>>> {
+        self.try_ipostdom_of_cfgnode(CFGNode::Block(block))
+    }
+
+    /// See notes on `try_ipostdom()`, but in addition, this will be `None` for
     /// `CFGNode::Return`
-    pub(crate) fn ipostdom_of_cfgnode(&self, node: CFGNode<'m>) -> Option<CFGNode<'m>> {
+    fn try_ipostdom_of_cfgnode(&self, node: CFGNode<'m>) -> Result<Option<CFGNode<'m>>> {
         let mut parents = self.graph.neighbors_directed(node, Direction::Incoming);
-        let ipostdom = parents.next()?;
-        if let Some(_) = parents.next() {
-            panic!(
-                "Block {:?} should have only one immediate postdominator",
-                node
-            );
+        let ipostdom = match parents.next() {
+            Some(ipostdom) => ipostdom,
+            None => return Ok(None),
+        };
+        if parents.next().is_some() {
+            return Err(Error::MalformedCFG {
+                function: self.function.name.clone(),
+                message: format!(
+                    "Block {:?} should have only one immediate postdominator",
+                    node
+                ),
+            });
         }
-        Some(ipostdom)
+        Ok(Some(ipostdom))
     }
 
     /// Get the children of the given basic block in the postdominator tree, i.e.,
@@ -313,25 +677,50 @@ impl<'m> PostDominatorTree<'m> {
     /// Get the children of `CFGNode::Return` in the postdominator tree, i.e.,
     /// get all the blocks which are immediately postdominated by `CFGNode::Return`.
     ///
+    /// `CFGNode::Unwind`, if present, is also immediately postdominated by
+    /// `CFGNode::Return`, but is not included here.
+    ///
     /// See notes on `ipostdom()`.
     pub fn children_of_return<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
         self.graph
             .neighbors_directed(CFGNode::Return, Direction::Outgoing)
-            .map(|child| match child {
-                CFGNode::Block(block) => block,
+            .filter_map(|child| match child {
+                CFGNode::Block(block) => Some(block),
+                CFGNode::Unwind => None,
                 CFGNode::Return => {
                     panic!("Return node shouldn't be the immediate postdominator of itself")
                 }
             })
     }
 
+    /// Get the children of `CFGNode::Unwind` in the postdominator tree, i.e.,
+    /// get all the blocks which are immediately postdominated by `CFGNode::Unwind`.
+    ///
+    /// This is always empty unless the `ControlFlowGraph` was built with
+    /// `ExceptionalFlow::SeparateUnwind`.
+    ///
+    /// See notes on `ipostdom()`.
+    pub fn children_of_unwind<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.graph
+            .neighbors_directed(CFGNode::Unwind, Direction::Outgoing)
+            .map(|child| match child {
+                CFGNode::Block(block) => block,
+                node => panic!(
+                    "{} node shouldn't be immediately postdominated by Unwind",
+                    node
+                ),
+            })
+    }
+
     /// Does `node_a` postdominate `node_b`?
     ///
     /// Note that every node postdominates itself by definition, so if
     /// `node_a == node_b`, this returns `true`.
     /// See also `strictly_postdominates()`
+    ///
+    /// This query takes constant time.
     pub fn postdominates(&self, node_a: CFGNode<'m>, node_b: CFGNode<'m>) -> bool {
-        petgraph::algo::has_path_connecting(&self.graph, node_a, node_b, None)
+        self.numbering.is_ancestor(node_a, node_b)
     }
 
     /// Does `node_a` strictly postdominate `node_b`?
@@ -341,4 +730,85 @@ impl<'m> PostDominatorTree<'m> {
     pub fn strictly_postdominates(&self, node_a: CFGNode<'m>, node_b: CFGNode<'m>) -> bool {
         node_a != node_b && self.postdominates(node_a, node_b)
     }
+
+    /// Get the nearest common postdominator of `node_a` and `node_b`, i.e., the
+    /// node which postdominates both `node_a` and `node_b` and is
+    /// postdominated by every other node that postdominates both of them.
+    ///
+    /// If one of the nodes postdominates the other, that node is the result.
+    /// Returns `None` if the function exit is unreachable from either node.
+    ///
+    /// This query takes time logarithmic in the number of blocks.
+    pub fn nearest_common_postdominator(
+        &self,
+        node_a: CFGNode<'m>,
+        node_b: CFGNode<'m>,
+    ) -> Option<CFGNode<'m>> {
+        self.numbering.nearest_common_ancestor(node_a, node_b)
+    }
+
+    /// Get the postdominance frontier of the basic block with the given `Name`.
+    ///
+    /// The postdominance frontier of bbX is the set of blocks bbY such that bbX
+    /// postdominates a successor of bbY, but does not strictly postdominate
+    /// bbY. These are exactly the blocks which bbX has an immediate control
+    /// dependence on.
+    ///
+    /// This will be empty for blocks from which the function exit is
+    /// unreachable.
+    pub fn postdominance_frontier<'s>(
+        &'s self,
+        block: &'m Name,
+    ) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        self.postdominance_frontier_of_cfgnode(CFGNode::Block(block))
+    }
+
+    pub(crate) fn postdominance_frontier_of_cfgnode<'s>(
+        &'s self,
+        node: CFGNode<'m>,
+    ) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        self.frontiers.get(&node).into_iter().flatten().copied()
+    }
+
+    /// Get the iterated postdominance frontier of the given set of basic
+    /// blocks.
+    ///
+    /// This is the analogue of `DominatorTree::iterated_dominance_frontier()`
+    /// for postdominance.
+    pub fn iterated_postdominance_frontier(
+        &self,
+        blocks: impl IntoIterator<Item = &'m Name>,
+    ) -> impl Iterator<Item = CFGNode<'m>> {
+        iterated_frontier(&self.frontiers, blocks.into_iter().map(CFGNode::Block)).into_iter()
+    }
+
+    /// Iterate over all the nodes in the postdominator tree, i.e., all the
+    /// nodes from which the function exit is reachable
+    pub(crate) fn nodes<'s>(&'s self) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        self.frontiers.keys().copied()
+    }
+
+    /// Render the postdominator tree in the Graphviz DOT format, with the given options.
+    ///
+    /// An edge from bbX to bbY indicates that bbX is the immediate postdominator of
+    /// bbY.
+    pub fn to_dot(&self, options: &DotOptions<'m>) -> String {
+        dot_to_string(|w| self.write_dot(w, options))
+    }
+
+    /// Write the postdominator tree in the Graphviz DOT format to `w`, with the given
+    /// options
+    pub fn write_dot(&self, w: &mut impl Write, options: &DotOptions<'m>) -> io::Result<()> {
+        write_digraph(
+            w,
+            &self.function.name,
+            "shape=box",
+            self.graph
+                .nodes()
+                .map(|node| cfgnode_dot_node(self.function, node, options)),
+            self.graph
+                .all_edges()
+                .map(|(from, to, _)| (from.to_string(), to.to_string(), None)),
+        )
+    }
 }
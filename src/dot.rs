@@ -0,0 +1,121 @@
+use crate::control_flow_graph::CFGNode;
+use crate::def_use::InstructionLocation;
+use llvm_ir::Function;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+/// Options controlling the output of the `to_dot()` and `write_dot()` methods,
+/// which render analyses in the [Graphviz] DOT format.
+///
+/// `DotOptions::default()` gives plain output: nodes labeled only with their
+/// block or function names, and no highlighting.
+///
+/// [Graphviz]: https://graphviz.org
+#[derive(Clone, Debug, Default)]
+pub struct DotOptions<'m> {
+    /// Include the text of each block's instructions and terminator in its
+    /// label, or in the `DataDependenceGraph` and `ProgramDependenceGraph`,
+    /// the text of each instruction. This has no effect on the `CallGraph`.
+    pub instructions: bool,
+
+    /// Label each edge of a `ControlFlowGraph` with its `CFGEdgeKind`(s), each
+    /// edge of a `CallGraph` with the `CallEdgeKind`(s) of its call sites, and
+    /// each edge of a `DataDependenceGraph` or `ProgramDependenceGraph` with
+    /// its `DependenceKind`(s). This has no effect on the other analyses,
+    /// whose edges have no kinds.
+    pub edge_kinds: bool,
+
+    /// Nodes to highlight, in the `ControlFlowGraph`, `DominatorTree`,
+    /// `PostDominatorTree`, and `ControlDependenceGraph`
+    pub highlight: HashSet<CFGNode<'m>>,
+
+    /// Names of functions to highlight in the `CallGraph`
+    pub highlight_functions: HashSet<&'m str>,
+
+    /// Instructions to highlight in the `DataDependenceGraph` and
+    /// `ProgramDependenceGraph`
+    pub highlight_instructions: HashSet<InstructionLocation<'m>>,
+}
+
+/// Write a DOT digraph with the given nodes and edges.
+///
+/// Nodes are given as (id, label, highlighted) triples; edges as (from, to,
+/// label) triples, where from and to are node ids.
+pub(crate) fn write_digraph(
+    w: &mut impl Write,
+    name: &str,
+    node_attrs: &str,
+    nodes: impl IntoIterator<Item = (String, String, bool)>,
+    edges: impl IntoIterator<Item = (String, String, Option<String>)>,
+) -> io::Result<()> {
+    writeln!(w, "digraph \"{}\" {{", escape(name))?;
+    writeln!(w, "  node [{}];", node_attrs)?;
+    for (id, label, highlighted) in nodes {
+        write!(w, "  \"{}\" [label=\"{}\"", escape(&id), label)?;
+        if highlighted {
+            write!(w, ", style=filled, fillcolor=yellow")?;
+        }
+        writeln!(w, "];")?;
+    }
+    for (from, to, label) in edges {
+        write!(w, "  \"{}\" -> \"{}\"", escape(&from), escape(&to))?;
+        if let Some(label) = label {
+            write!(w, " [label=\"{}\"]", escape(&label))?;
+        }
+        writeln!(w, ";")?;
+    }
+    writeln!(w, "}}")
+}
+
+/// Collect the output of a `write_dot()` method into a `String`
+pub(crate) fn dot_to_string(write_dot: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> String {
+    let mut bytes = vec![];
+    write_dot(&mut bytes).expect("writing to a Vec shouldn't fail");
+    String::from_utf8(bytes).expect("DOT output should be valid UTF-8")
+}
+
+/// Get the DOT node for a `CFGNode` in the given `function`, as expected by
+/// `write_digraph()`. The label is already escaped.
+pub(crate) fn cfgnode_dot_node<'m>(
+    function: &'m Function,
+    node: CFGNode<'m>,
+    options: &DotOptions<'m>,
+) -> (String, String, bool) {
+    let id = node.to_string();
+    let mut label = escape(&id);
+    if options.instructions {
+        if let CFGNode::Block(name) = node {
+            if let Some(bb) = function.get_bb_by_name(name) {
+                // `\l` ends a left-justified line
+                label.push_str(":\\l");
+                for inst in &bb.instrs {
+                    push_line(&mut label, inst);
+                }
+                push_line(&mut label, &bb.term);
+            }
+        }
+    }
+    let highlighted = options.highlight.contains(&node);
+    (id, label, highlighted)
+}
+
+fn push_line(label: &mut String, item: &impl Display) {
+    label.push_str("  ");
+    label.push_str(&escape(&item.to_string()));
+    label.push_str("\\l");
+}
+
+/// Escape a string for use inside a double-quoted DOT string
+pub(crate) fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -2,17 +2,71 @@
 use std::fmt;
 use std::result;
 
-#[derive(Debug)]
+/// Errors returned by this crate's fallible methods.
+///
+/// More variants may be added in the future, so `match`es on `Error` need a
+/// wildcard arm.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
 pub enum Error {
-    CallGraph(String),
+    /// No function with the given name was found in the analyzed `Module`(s)
+    FunctionNotFound {
+        /// Name of the function
+        name: String,
+    },
+    /// No module with the given name was found in the analyzed `Module`(s)
+    ModuleNotFound {
+        /// Name of the module
+        name: String,
+    },
+    /// Functions with the same name were found in more than one of the
+    /// analyzed `Module`(s)
+    DuplicateSymbol {
+        /// Name of the function
+        name: String,
+        /// Name of the first module it was found in
+        first_module: String,
+        /// Name of the second module it was found in
+        second_module: String,
+    },
+    /// A control-flow graph (or an analysis derived from one) is not shaped as
+    /// expected
+    MalformedCFG {
+        /// Name of the function the control-flow graph is for
+        function: String,
+        /// Description of the problem
+        message: String,
+    },
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            Error::CallGraph(ref err) => write!(f, "{err}"),
+        match self {
+            Error::FunctionNotFound { name } => {
+                write!(f, "Function named {:?} not found in the Module(s)", name)
+            }
+            Error::ModuleNotFound { name } => {
+                write!(f, "Module named {:?} not found in the Module(s)", name)
+            }
+            Error::DuplicateSymbol {
+                name,
+                first_module,
+                second_module,
+            } => write!(
+                f,
+                "Multiple functions found with name {:?}: one in module {:?}, another in module {:?}",
+                name, first_module, second_module
+            ),
+            Error::MalformedCFG { function, message } => {
+                write!(f, "Malformed CFG in function {:?}: {}", function, message)
+            }
         }
     }
 }
 
-pub type Result<T> = result::Result<T, Error>;
+impl std::error::Error for Error {}
+
+/// A `Result` whose error type defaults to this crate's `Error`. The error
+/// type can still be given explicitly, so that glob imports of this crate
+/// don't break uses of `Result<T, E>`.
+pub type Result<T, E = Error> = result::Result<T, E>;
@@ -1,4 +1,5 @@
-use llvm_ir::{Module, TypeRef};
+use crate::ModuleFunctions;
+use llvm_ir::TypeRef;
 use std::collections::{HashMap, HashSet};
 
 /// Allows you to iterate over all the functions in the analyzed `Module`(s) that
@@ -11,10 +12,13 @@ pub struct FunctionsByType<'m> {
 }
 
 impl<'m> FunctionsByType<'m> {
-    pub(crate) fn new(modules: impl IntoIterator<Item = &'m Module>) -> Self {
+    pub(crate) fn new<'a>(modules: impl IntoIterator<Item = ModuleFunctions<'m, 'a>>) -> Self
+    where
+        'm: 'a,
+    {
         let mut map: HashMap<TypeRef, HashSet<&'m str>> = HashMap::new();
-        for module in modules {
-            for func in &module.functions {
+        for ModuleFunctions { module, functions } in modules {
+            for &func in functions {
                 map.entry(module.type_of(func))
                     .or_default()
                     .insert(&func.name);
@@ -29,7 +33,12 @@ impl<'m> FunctionsByType<'m> {
         self.map
             .get(ty)
             .into_iter()
-            .map(|hs| hs.iter().copied())
-            .flatten()
+            .flat_map(|hs| hs.iter().copied())
+    }
+
+    /// Iterate over all of the functions in the analyzed `Module`(s), of any
+    /// type
+    pub(crate) fn functions<'s>(&'s self) -> impl Iterator<Item = &'m str> + 's {
+        self.map.values().flat_map(|hs| hs.iter().copied())
     }
 }
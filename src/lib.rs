@@ -4,22 +4,48 @@
 //! For a more thorough introduction to the crate and how to get started,
 //! see the [crate's README](https://github.com/cdisselkoen/llvm-ir-analysis/blob/main/README.md).
 
+mod address_taken;
+mod analysis_manager;
 mod call_graph;
 mod control_dep_graph;
 mod control_flow_graph;
+mod data_dep_graph;
+mod dataflow;
+mod dead_functions;
+mod def_use;
 mod dominator_tree;
+mod dot;
 mod error;
 mod functions_by_type;
+mod liveness;
+mod loop_info;
+mod operands;
+mod program_dep_graph;
 
-pub use crate::call_graph::CallGraph;
+pub use crate::address_taken::AddressTakenFunctions;
+use crate::analysis_manager::AnalysisManager;
+pub use crate::analysis_manager::{FunctionAnalysisPass, ModuleAnalysisPass};
+pub use crate::call_graph::{CallEdgeKind, CallGraph, CallInstKind, CallSite, IndirectCallTargets};
 pub use crate::control_dep_graph::ControlDependenceGraph;
-pub use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
+pub use crate::control_flow_graph::{CFGEdgeKind, CFGNode, ControlFlowGraph, ExceptionalFlow};
+pub use crate::data_dep_graph::{DataDependenceGraph, DependenceKind, DependenceOptions};
+pub use crate::dataflow::{DataflowAnalysis, DataflowDirection, DataflowResult};
+pub use crate::dead_functions::{DeadFunctions, EntryPoints};
+pub use crate::def_use::{DefUseInfo, Definition, InstructionLocation, Use};
 pub use crate::dominator_tree::{DominatorTree, PostDominatorTree};
+pub use crate::dot::DotOptions;
+pub use crate::error::{Error, Result};
 pub use crate::functions_by_type::FunctionsByType;
+pub use crate::liveness::Liveness;
+pub use crate::loop_info::{IrreducibleRegion, Loop, LoopInfo};
+pub use crate::program_dep_graph::{ProgramDependenceGraph, Slice};
 use llvm_ir::{Function, Module};
 use log::debug;
-use std::cell::{Ref, RefCell};
+use once_cell::sync::OnceCell;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 // Re-export the llvm-ir crate so that our consumers can have only one Cargo.toml entry and don't
 // have to worry about matching versions.
@@ -29,13 +55,22 @@ pub use llvm_ir;
 pub struct ModuleAnalysis<'m> {
     /// Reference to the `llvm-ir` `Module`
     module: &'m Module,
-    /// Call graph for the module
-    call_graph: SimpleCache<CallGraph<'m>>,
+    /// The functions to analyze, in the order they appear in the `Module`.
+    /// These are the `Module`'s own functions, except for any which were
+    /// replaced with `replace_function()`, followed by any added with
+    /// `add_function()`.
+    functions: Vec<&'m Function>,
+    /// Call graph for the module, for each `IndirectCallTargets`
+    call_graph: PerIndirectCallTargets<SimpleCache<CallGraph<'m>>>,
     /// `FunctionsByType`, which allows you to iterate over the module's
     /// functions by type
     functions_by_type: SimpleCache<FunctionsByType<'m>>,
+    /// The module's functions whose address is taken
+    address_taken_functions: SimpleCache<AddressTakenFunctions<'m>>,
     /// Map from function name to the `FunctionAnalysis` for that function
     fn_analyses: HashMap<&'m str, FunctionAnalysis<'m>>,
+    /// Custom analyses of the module
+    custom_analyses: AnalysisManager,
 }
 
 impl<'m> ModuleAnalysis<'m> {
@@ -46,47 +81,281 @@ impl<'m> ModuleAnalysis<'m> {
     pub fn new(module: &'m Module) -> Self {
         Self {
             module,
-            call_graph: SimpleCache::new(),
+            functions: module.functions.iter().collect(),
+            call_graph: PerIndirectCallTargets::new(SimpleCache::new),
             functions_by_type: SimpleCache::new(),
+            address_taken_functions: SimpleCache::new(),
             fn_analyses: module
                 .functions
                 .iter()
                 .map(|f| (f.name.as_str(), FunctionAnalysis::new(f)))
                 .collect(),
+            custom_analyses: AnalysisManager::new(),
         }
     }
 
     /// Get a reference to the `Module` which the `ModuleAnalysis` was created
     /// with.
+    ///
+    /// This doesn't reflect any functions replaced with `replace_function()`
+    /// or added with `add_function()`; to iterate over the functions being
+    /// analyzed, use `functions()`.
     pub fn module(&self) -> &'m Module {
         self.module
     }
 
-    /// Get the `CallGraph` for the `Module`.
-    pub fn call_graph(&self) -> Ref<CallGraph<'m>> {
-        self.call_graph.get_or_insert_with(|| {
-            let functions_by_type = self.functions_by_type();
-            debug!("computing single-module call graph");
-            CallGraph::new(std::iter::once(self.module), &functions_by_type)
+    /// Iterate over the `Function`s being analyzed, in the order they appear
+    /// in the `Module`. These are the `Module`'s own functions, except for any
+    /// which were replaced with `replace_function()`, followed by any added
+    /// with `add_function()`.
+    pub fn functions<'s>(&'s self) -> impl Iterator<Item = &'m Function> + 's {
+        self.functions.iter().copied()
+    }
+
+    /// The `Module` with the functions being analyzed, for constructing
+    /// `Module`-wide analyses
+    fn module_functions(&self) -> ModuleFunctions<'m, '_> {
+        ModuleFunctions {
+            module: self.module,
+            functions: &self.functions,
+        }
+    }
+
+    /// Get the custom analysis `A` for the `Module`, computing it with
+    /// `A::compute()` if it hasn't been computed yet. See
+    /// [`ModuleAnalysisPass`](trait.ModuleAnalysisPass.html).
+    pub fn analysis<A: ModuleAnalysisPass>(&self) -> Arc<A> {
+        self.custom_analyses.get_or_insert_with(|| {
+            debug!(
+                "computing custom analysis {} for module {:?}",
+                std::any::type_name::<A>(),
+                &self.module.name
+            );
+            A::compute(self)
         })
     }
 
+    /// Analyze the given `Function` in place of the `Module`'s function of the
+    /// same name, e.g. after editing a copy of that function. The analyses of
+    /// the replaced function are discarded, along with all the `Module`-wide
+    /// analyses, as these may depend on it (see `invalidate_function()`);
+    /// analyses of the other functions are kept.
+    ///
+    /// Only the functions being analyzed are affected: `module()` still
+    /// returns the original `Module`, so `Module`-wide analyses (including
+    /// custom `ModuleAnalysisPass`es) should iterate over `functions()` rather
+    /// than `module().functions`. Global variables are always those of the
+    /// original `Module`, so e.g. `AddressTakenFunctions` doesn't see function
+    /// pointers stored in global variables added after the fact.
+    ///
+    /// Error if no function of that name exists in the `Module`.
+    pub fn replace_function(&mut self, function: &'m Function) -> Result<()> {
+        let func_name = function.name.as_str();
+        let index = self
+            .functions
+            .iter()
+            .position(|f| f.name == func_name)
+            .ok_or_else(|| Error::FunctionNotFound {
+                name: func_name.to_owned(),
+            })?;
+        debug!(
+            "replacing function {} in module {:?}",
+            func_name, &self.module.name
+        );
+        self.functions[index] = function;
+        self.fn_analyses
+            .insert(func_name, FunctionAnalysis::new(function));
+        self.invalidate_module_analyses();
+        Ok(())
+    }
+
+    /// Analyze the given `Function` in addition to the `Module`'s functions,
+    /// e.g. a function added by instrumentation. The `Module`-wide analyses
+    /// are discarded, as they may depend on it; analyses of the other
+    /// functions are kept. See notes on `replace_function()`.
+    ///
+    /// Error if a function of that name already exists in the `Module` (or
+    /// was already added).
+    pub fn add_function(&mut self, function: &'m Function) -> Result<()> {
+        let func_name = function.name.as_str();
+        if self.fn_analyses.contains_key(func_name) {
+            return Err(Error::DuplicateSymbol {
+                name: func_name.to_owned(),
+                first_module: self.module.name.clone(),
+                second_module: self.module.name.clone(),
+            });
+        }
+        debug!(
+            "adding function {} to module {:?}",
+            func_name, &self.module.name
+        );
+        self.functions.push(function);
+        self.fn_analyses
+            .insert(func_name, FunctionAnalysis::new(function));
+        self.invalidate_module_analyses();
+        Ok(())
+    }
+
+    /// Discard the cached analyses of the function with the given name
+    /// (see `FunctionAnalysis::invalidate()`), along with all the
+    /// `Module`-wide analyses (see `invalidate_module_analyses()`), as these
+    /// may depend on the function.
+    ///
+    /// The analyses are recomputed from the same `Function`, so this can't be
+    /// used to analyze an edited function; for that, use `replace_function()`.
+    ///
+    /// Error if no function of that name exists in the `Module`.
+    pub fn invalidate_function(&mut self, func_name: &str) -> Result<()> {
+        match self.fn_analyses.get_mut(func_name) {
+            Some(fn_analysis) => fn_analysis.invalidate(),
+            None => {
+                return Err(Error::FunctionNotFound {
+                    name: func_name.to_owned(),
+                })
+            }
+        }
+        self.invalidate_module_analyses();
+        Ok(())
+    }
+
+    /// Discard the cached `Module`-wide analyses, such as the `CallGraph` and
+    /// custom `ModuleAnalysisPass`es, so that they will be recomputed the next
+    /// time they are requested. Analyses of individual functions are kept.
+    pub fn invalidate_module_analyses(&mut self) {
+        debug!("invalidating analyses of module {:?}", &self.module.name);
+        self.call_graph.iter_mut().for_each(SimpleCache::clear);
+        self.functions_by_type.clear();
+        self.address_taken_functions.clear();
+        self.custom_analyses.clear();
+    }
+
+    /// Discard all cached analyses, both `Module`-wide and of individual
+    /// functions.
+    ///
+    /// Note that while a `ModuleAnalysis` exists, the `Module` it borrows
+    /// can't be modified; to analyze an edited function, use
+    /// `replace_function()`. The `invalidate` methods are useful, e.g., for
+    /// custom analyses which depend on state outside of the `Module`, or to
+    /// free memory.
+    pub fn invalidate_all(&mut self) {
+        self.fn_analyses
+            .values_mut()
+            .for_each(FunctionAnalysis::invalidate);
+        self.invalidate_module_analyses();
+    }
+
+    /// Get the `CallGraph` for the `Module`.
+    ///
+    /// This uses `IndirectCallTargets::AddressTaken`; see `call_graph_with()`
+    /// for other options.
+    pub fn call_graph(&self) -> &CallGraph<'m> {
+        self.call_graph_with(IndirectCallTargets::AddressTaken)
+    }
+
+    /// Get the `CallGraph` for the `Module`, resolving calls through function
+    /// pointers as specified by `indirect_call_targets`.
+    pub fn call_graph_with(&self, indirect_call_targets: IndirectCallTargets) -> &CallGraph<'m> {
+        self.call_graph
+            .get(indirect_call_targets)
+            .get_or_insert_with(|| {
+                let functions_by_type = self.functions_by_type();
+                let address_taken = match indirect_call_targets {
+                    IndirectCallTargets::AddressTaken => Some(self.address_taken_functions()),
+                    IndirectCallTargets::MatchingType => None,
+                };
+                debug!(
+                    "computing single-module call graph ({:?})",
+                    indirect_call_targets
+                );
+                CallGraph::new(
+                    std::iter::once(self.module_functions()),
+                    functions_by_type,
+                    address_taken,
+                )
+            })
+    }
+
     /// Get the `FunctionsByType` for the `Module`.
-    pub fn functions_by_type(&self) -> Ref<FunctionsByType<'m>> {
+    pub fn functions_by_type(&self) -> &FunctionsByType<'m> {
         self.functions_by_type.get_or_insert_with(|| {
             debug!("computing single-module functions-by-type");
-            FunctionsByType::new(std::iter::once(self.module))
+            FunctionsByType::new(std::iter::once(self.module_functions()))
+        })
+    }
+
+    /// Get the `AddressTakenFunctions` for the `Module`.
+    pub fn address_taken_functions(&self) -> &AddressTakenFunctions<'m> {
+        self.address_taken_functions.get_or_insert_with(|| {
+            debug!("computing single-module address-taken functions");
+            AddressTakenFunctions::new(std::iter::once(self.module_functions()))
         })
     }
 
+    /// Compute the `DeadFunctions` in the `Module`, given its entry points.
+    ///
+    /// Unlike most analyses, this is not cached, as it depends on the
+    /// `entry_points`.
+    ///
+    /// Error if any of the named `entry_points` is not found in the `Module`.
+    pub fn dead_functions(&self, entry_points: EntryPoints) -> Result<DeadFunctions<'m>> {
+        debug!("computing single-module dead functions");
+        DeadFunctions::new(
+            std::iter::once(self.module_functions()),
+            self.call_graph(),
+            self.address_taken_functions(),
+            entry_points,
+        )
+    }
+
     /// Get the `FunctionAnalysis` for the function with the given name.
     ///
     /// Panics if no function of that name exists in the `Module` which the
-    /// `ModuleAnalysis` was created with.
+    /// `ModuleAnalysis` was created with; see `try_fn_analysis()` for a
+    /// non-panicking version.
     pub fn fn_analysis<'s>(&'s self, func_name: &str) -> &'s FunctionAnalysis<'m> {
+        self.try_fn_analysis(func_name)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the `FunctionAnalysis` for the function with the given name.
+    ///
+    /// Error if no function of that name exists in the `Module` which the
+    /// `ModuleAnalysis` was created with.
+    pub fn try_fn_analysis<'s>(&'s self, func_name: &str) -> Result<&'s FunctionAnalysis<'m>> {
         self.fn_analyses
             .get(func_name)
-            .unwrap_or_else(|| panic!("Function named {:?} not found in the Module", func_name))
+            .ok_or_else(|| Error::FunctionNotFound {
+                name: func_name.to_owned(),
+            })
+    }
+
+    /// Eagerly compute the given analyses for every function in the `Module`,
+    /// so that later requests for them are just cache lookups.
+    ///
+    /// With the `rayon` feature, the functions are analyzed in parallel.
+    ///
+    /// This uses `ExceptionalFlow::MergeWithReturn`; see `precompute_with()`
+    /// for other options.
+    pub fn precompute(&self, kinds: &[AnalysisKind]) {
+        self.precompute_with(kinds, ExceptionalFlow::MergeWithReturn)
+    }
+
+    /// Eagerly compute the given analyses for every function in the `Module`,
+    /// representing exceptional control flow as specified by
+    /// `exceptional_flow`. See notes on `precompute()`.
+    pub fn precompute_with(&self, kinds: &[AnalysisKind], exceptional_flow: ExceptionalFlow) {
+        debug!(
+            "precomputing {:?} for all functions in module {:?}",
+            kinds, &self.module.name
+        );
+        #[cfg(feature = "rayon")]
+        self.fn_analyses
+            .par_iter()
+            .for_each(|(_, fn_analysis)| fn_analysis.precompute_with(kinds, exceptional_flow));
+        #[cfg(not(feature = "rayon"))]
+        self.fn_analyses
+            .values()
+            .for_each(|fn_analysis| fn_analysis.precompute_with(kinds, exceptional_flow));
     }
 }
 
@@ -96,10 +365,12 @@ impl<'m> ModuleAnalysis<'m> {
 pub struct CrossModuleAnalysis<'m> {
     /// Reference to the `llvm-ir` `Module`s
     modules: Vec<&'m Module>,
-    /// Cross-module call graph
-    call_graph: SimpleCache<CallGraph<'m>>,
+    /// Cross-module call graph, for each `IndirectCallTargets`
+    call_graph: PerIndirectCallTargets<SimpleCache<CallGraph<'m>>>,
     /// `FunctionsByType`, which allows you to iterate over functions by type
     functions_by_type: SimpleCache<FunctionsByType<'m>>,
+    /// Functions whose address is taken
+    address_taken_functions: SimpleCache<AddressTakenFunctions<'m>>,
     /// Map from module name to the `ModuleAnalysis` for that module
     module_analyses: HashMap<&'m str, ModuleAnalysis<'m>>,
 }
@@ -118,8 +389,9 @@ impl<'m> CrossModuleAnalysis<'m> {
             .collect();
         Self {
             modules,
-            call_graph: SimpleCache::new(),
+            call_graph: PerIndirectCallTargets::new(SimpleCache::new),
             functions_by_type: SimpleCache::new(),
+            address_taken_functions: SimpleCache::new(),
             module_analyses,
         }
     }
@@ -129,58 +401,269 @@ impl<'m> CrossModuleAnalysis<'m> {
         self.modules.iter().copied()
     }
 
-    /// Iterate over all the `Function`s in the analyzed `Module`(s).
+    /// Iterate over all the `Function`s in the analyzed `Module`(s), including
+    /// any which replaced the `Module`s' own functions or were added to them
+    /// (see `replace_function()` and `add_function()`).
     pub fn functions<'s>(&'s self) -> impl Iterator<Item = &'m Function> + 's {
-        self.modules().map(|m| m.functions.iter()).flatten()
+        self.module_functions()
+            .flat_map(|m| m.functions.iter().copied())
+    }
+
+    /// The `Module`s with the functions being analyzed, for constructing
+    /// cross-module analyses
+    fn module_functions<'s>(&'s self) -> impl Iterator<Item = ModuleFunctions<'m, 's>> + 's {
+        self.modules
+            .iter()
+            .map(move |module| self.module_analyses[module.name.as_str()].module_functions())
     }
 
     /// Get the full `CallGraph` for the `Module`(s).
     ///
     /// This will include both cross-module and within-module calls.
-    pub fn call_graph(&self) -> Ref<CallGraph<'m>> {
-        self.call_graph.get_or_insert_with(|| {
-            let functions_by_type = self.functions_by_type();
-            debug!("computing multi-module call graph");
-            CallGraph::new(self.modules(), &functions_by_type)
-        })
+    ///
+    /// This uses `IndirectCallTargets::AddressTaken`; see `call_graph_with()`
+    /// for other options.
+    pub fn call_graph(&self) -> &CallGraph<'m> {
+        self.call_graph_with(IndirectCallTargets::AddressTaken)
+    }
+
+    /// Get the full `CallGraph` for the `Module`(s), resolving calls through
+    /// function pointers as specified by `indirect_call_targets`.
+    pub fn call_graph_with(&self, indirect_call_targets: IndirectCallTargets) -> &CallGraph<'m> {
+        self.call_graph
+            .get(indirect_call_targets)
+            .get_or_insert_with(|| {
+                let functions_by_type = self.functions_by_type();
+                let address_taken = match indirect_call_targets {
+                    IndirectCallTargets::AddressTaken => Some(self.address_taken_functions()),
+                    IndirectCallTargets::MatchingType => None,
+                };
+                debug!(
+                    "computing multi-module call graph ({:?})",
+                    indirect_call_targets
+                );
+                CallGraph::new(self.module_functions(), functions_by_type, address_taken)
+            })
     }
 
     /// Get the `FunctionsByType` for the `Module`(s).
-    pub fn functions_by_type(&self) -> Ref<FunctionsByType<'m>> {
+    pub fn functions_by_type(&self) -> &FunctionsByType<'m> {
         self.functions_by_type.get_or_insert_with(|| {
             debug!("computing multi-module functions-by-type");
-            FunctionsByType::new(self.modules())
+            FunctionsByType::new(self.module_functions())
         })
     }
 
+    /// Get the `AddressTakenFunctions` for the `Module`(s).
+    pub fn address_taken_functions(&self) -> &AddressTakenFunctions<'m> {
+        self.address_taken_functions.get_or_insert_with(|| {
+            debug!("computing multi-module address-taken functions");
+            AddressTakenFunctions::new(self.module_functions())
+        })
+    }
+
+    /// Compute the `DeadFunctions` in the `Module`(s), given their entry
+    /// points.
+    ///
+    /// Unlike most analyses, this is not cached, as it depends on the
+    /// `entry_points`.
+    ///
+    /// Error if any of the named `entry_points` is not found in the
+    /// `Module`(s).
+    pub fn dead_functions(&self, entry_points: EntryPoints) -> Result<DeadFunctions<'m>> {
+        debug!("computing multi-module dead functions");
+        DeadFunctions::new(
+            self.module_functions(),
+            self.call_graph(),
+            self.address_taken_functions(),
+            entry_points,
+        )
+    }
+
     /// Get the `ModuleAnalysis` for the module with the given name.
     ///
     /// Panics if no module of that name exists in the `Module`(s) which the
-    /// `CrossModuleAnalysis` was created with.
+    /// `CrossModuleAnalysis` was created with; see `try_module_analysis()` for
+    /// a non-panicking version.
     pub fn module_analysis<'s>(&'s self, mod_name: &str) -> &'s ModuleAnalysis<'m> {
-        self.module_analyses.get(mod_name).unwrap_or_else(|| {
-            panic!(
-                "Module named {:?} not found in the CrossModuleAnalysis",
-                mod_name
-            )
-        })
+        self.try_module_analysis(mod_name)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the `ModuleAnalysis` for the module with the given name.
+    ///
+    /// Error if no module of that name exists in the `Module`(s) which the
+    /// `CrossModuleAnalysis` was created with.
+    pub fn try_module_analysis<'s>(&'s self, mod_name: &str) -> Result<&'s ModuleAnalysis<'m>> {
+        self.module_analyses
+            .get(mod_name)
+            .ok_or_else(|| Error::ModuleNotFound {
+                name: mod_name.to_owned(),
+            })
+    }
+
+    /// Eagerly compute the given analyses for every function in the
+    /// `Module`(s), so that later requests for them are just cache lookups.
+    ///
+    /// With the `rayon` feature, the functions are analyzed in parallel.
+    ///
+    /// This uses `ExceptionalFlow::MergeWithReturn`; see `precompute_with()`
+    /// for other options.
+    pub fn precompute(&self, kinds: &[AnalysisKind]) {
+        self.precompute_with(kinds, ExceptionalFlow::MergeWithReturn)
+    }
+
+    /// Eagerly compute the given analyses for every function in the
+    /// `Module`(s), representing exceptional control flow as specified by
+    /// `exceptional_flow`. See notes on `precompute()`.
+    pub fn precompute_with(&self, kinds: &[AnalysisKind], exceptional_flow: ExceptionalFlow) {
+        #[cfg(feature = "rayon")]
+        self.module_analyses
+            .par_iter()
+            .for_each(|(_, module_analysis)| {
+                module_analysis.precompute_with(kinds, exceptional_flow)
+            });
+        #[cfg(not(feature = "rayon"))]
+        self.module_analyses
+            .values()
+            .for_each(|module_analysis| module_analysis.precompute_with(kinds, exceptional_flow));
+    }
+
+    /// Analyze the given `Function` in place of the function of the same name
+    /// in the `Module`(s). The analyses of the replaced function are
+    /// discarded, along with the `Module`-wide analyses of the module
+    /// containing it and the cross-module analyses. See
+    /// `ModuleAnalysis::replace_function()`.
+    ///
+    /// Error if no function of that name exists in the `Module`(s), or if
+    /// functions of that name exist in more than one of them.
+    pub fn replace_function(&mut self, function: &'m Function) -> Result<()> {
+        let module = match self.try_get_func_by_name(&function.name)? {
+            Some((_, module)) => module,
+            None => {
+                return Err(Error::FunctionNotFound {
+                    name: function.name.clone(),
+                })
+            }
+        };
+        self.module_analyses
+            .get_mut(module.name.as_str())
+            .expect("module containing the function should have a ModuleAnalysis")
+            .replace_function(function)?;
+        self.invalidate_cross_module_analyses();
+        Ok(())
+    }
+
+    /// Analyze the given `Function` in addition to the functions of the
+    /// module with the given name. The `Module`-wide analyses of that module
+    /// and the cross-module analyses are discarded. See
+    /// `ModuleAnalysis::add_function()`.
+    ///
+    /// Error if no module of that name exists in the `Module`(s), or if a
+    /// function of that name already exists in any of them.
+    pub fn add_function(&mut self, mod_name: &str, function: &'m Function) -> Result<()> {
+        if !self.module_analyses.contains_key(mod_name) {
+            return Err(Error::ModuleNotFound {
+                name: mod_name.to_owned(),
+            });
+        }
+        if let Some((_, module)) = self.try_get_func_by_name(&function.name)? {
+            return Err(Error::DuplicateSymbol {
+                name: function.name.clone(),
+                first_module: module.name.clone(),
+                second_module: mod_name.to_owned(),
+            });
+        }
+        self.module_analyses
+            .get_mut(mod_name)
+            .expect("module should have a ModuleAnalysis")
+            .add_function(function)?;
+        self.invalidate_cross_module_analyses();
+        Ok(())
+    }
+
+    /// Discard the cached analyses of the function with the given name, along
+    /// with the `Module`-wide analyses of the module containing it and the
+    /// cross-module analyses, as these may depend on the function. See
+    /// `ModuleAnalysis::invalidate_function()`.
+    ///
+    /// Error if no function of that name exists in the `Module`(s), or if
+    /// functions of that name exist in more than one of them.
+    pub fn invalidate_function(&mut self, func_name: &str) -> Result<()> {
+        let module = match self.try_get_func_by_name(func_name)? {
+            Some((_, module)) => module,
+            None => {
+                return Err(Error::FunctionNotFound {
+                    name: func_name.to_owned(),
+                })
+            }
+        };
+        self.module_analyses
+            .get_mut(module.name.as_str())
+            .expect("module containing the function should have a ModuleAnalysis")
+            .invalidate_function(func_name)?;
+        self.invalidate_cross_module_analyses();
+        Ok(())
+    }
+
+    /// Discard the cached cross-module analyses, such as the cross-module
+    /// `CallGraph`, so that they will be recomputed the next time they are
+    /// requested. Analyses of individual modules and functions are kept.
+    pub fn invalidate_cross_module_analyses(&mut self) {
+        debug!("invalidating multi-module analyses");
+        self.call_graph.iter_mut().for_each(SimpleCache::clear);
+        self.functions_by_type.clear();
+        self.address_taken_functions.clear();
+    }
+
+    /// Discard all cached analyses: cross-module, `Module`-wide, and of
+    /// individual functions. See notes on `ModuleAnalysis::invalidate_all()`.
+    pub fn invalidate_all(&mut self) {
+        self.module_analyses
+            .values_mut()
+            .for_each(ModuleAnalysis::invalidate_all);
+        self.invalidate_cross_module_analyses();
     }
 
     /// Get the `Function` with the given name from the analyzed `Module`(s).
     ///
     /// Returns both the `Function` and the `Module` it was found in, or `None`
     /// if no function was found with that name.
+    ///
+    /// Panics if functions with that name are found in more than one of the
+    /// `Module`(s); see `try_get_func_by_name()` for a non-panicking version.
     pub fn get_func_by_name(&self, func_name: &str) -> Option<(&'m Function, &'m Module)> {
+        self.try_get_func_by_name(func_name)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the `Function` with the given name from the analyzed `Module`(s).
+    ///
+    /// Returns both the `Function` and the `Module` it was found in, or `None`
+    /// if no function was found with that name.
+    ///
+    /// Error if functions with that name are found in more than one of the
+    /// `Module`(s).
+    pub fn try_get_func_by_name(
+        &self,
+        func_name: &str,
+    ) -> Result<Option<(&'m Function, &'m Module)>> {
         let mut retval = None;
-        for &module in &self.modules {
-            if let Some(func) = module.get_func_by_name(func_name) {
+        for ModuleFunctions { module, functions } in self.module_functions() {
+            if let Some(&func) = functions.iter().find(|f| f.name == func_name) {
                 match retval {
                     None => retval = Some((func, module)),
-                    Some((_, retmod)) => panic!("Multiple functions found with name {:?}: one in module {:?}, another in module {:?}", func_name, &retmod.name, &module.name),
+                    Some((_, retmod)) => {
+                        return Err(Error::DuplicateSymbol {
+                            name: func_name.to_owned(),
+                            first_module: retmod.name.clone(),
+                            second_module: module.name.clone(),
+                        })
+                    }
                 }
             }
         }
-        retval
+        Ok(retval)
     }
 }
 
@@ -188,14 +671,27 @@ impl<'m> CrossModuleAnalysis<'m> {
 pub struct FunctionAnalysis<'m> {
     /// Reference to the `llvm-ir` `Function`
     function: &'m Function,
-    /// Control flow graph for the function
-    control_flow_graph: SimpleCache<ControlFlowGraph<'m>>,
-    /// Dominator tree for the function
-    dominator_tree: SimpleCache<DominatorTree<'m>>,
-    /// Postdominator tree for the function
-    postdominator_tree: SimpleCache<PostDominatorTree<'m>>,
-    /// Control dependence graph for the function
-    control_dep_graph: SimpleCache<ControlDependenceGraph<'m>>,
+    /// Control flow graph for the function, for each `ExceptionalFlow`
+    control_flow_graph: PerExceptionalFlow<SimpleCache<ControlFlowGraph<'m>>>,
+    /// Dominator tree for the function, for each `ExceptionalFlow`
+    dominator_tree: PerExceptionalFlow<SimpleCache<DominatorTree<'m>>>,
+    /// Postdominator tree for the function, for each `ExceptionalFlow`
+    postdominator_tree: PerExceptionalFlow<SimpleCache<PostDominatorTree<'m>>>,
+    /// Control dependence graph for the function, for each `ExceptionalFlow`
+    control_dep_graph: PerExceptionalFlow<SimpleCache<ControlDependenceGraph<'m>>>,
+    /// Loop info for the function, for each `ExceptionalFlow`
+    loop_info: PerExceptionalFlow<SimpleCache<LoopInfo<'m>>>,
+    /// Liveness of SSA values in the function, for each `ExceptionalFlow`
+    liveness: PerExceptionalFlow<SimpleCache<Liveness<'m>>>,
+    /// Def-use and use-def chains for the function
+    def_use_info: SimpleCache<DefUseInfo<'m>>,
+    /// Data dependence graph for the function, for each `ExceptionalFlow`
+    data_dep_graph: PerExceptionalFlow<PerDependenceOptions<SimpleCache<DataDependenceGraph<'m>>>>,
+    /// Program dependence graph for the function, for each `ExceptionalFlow`
+    program_dep_graph:
+        PerExceptionalFlow<PerDependenceOptions<SimpleCache<ProgramDependenceGraph<'m>>>>,
+    /// Custom analyses of the function
+    custom_analyses: AnalysisManager,
 }
 
 impl<'m> FunctionAnalysis<'m> {
@@ -206,80 +702,560 @@ impl<'m> FunctionAnalysis<'m> {
     pub fn new(function: &'m Function) -> Self {
         Self {
             function,
-            control_flow_graph: SimpleCache::new(),
-            dominator_tree: SimpleCache::new(),
-            postdominator_tree: SimpleCache::new(),
-            control_dep_graph: SimpleCache::new(),
+            control_flow_graph: PerExceptionalFlow::new(SimpleCache::new),
+            dominator_tree: PerExceptionalFlow::new(SimpleCache::new),
+            postdominator_tree: PerExceptionalFlow::new(SimpleCache::new),
+            control_dep_graph: PerExceptionalFlow::new(SimpleCache::new),
+            loop_info: PerExceptionalFlow::new(SimpleCache::new),
+            liveness: PerExceptionalFlow::new(SimpleCache::new),
+            def_use_info: SimpleCache::new(),
+            data_dep_graph: PerExceptionalFlow::new(|| PerDependenceOptions::new(SimpleCache::new)),
+            program_dep_graph: PerExceptionalFlow::new(|| {
+                PerDependenceOptions::new(SimpleCache::new)
+            }),
+            custom_analyses: AnalysisManager::new(),
         }
     }
 
-    /// Get the `ControlFlowGraph` for the function.
-    pub fn control_flow_graph(&self) -> Ref<ControlFlowGraph<'m>> {
-        self.control_flow_graph.get_or_insert_with(|| {
-            debug!("computing control flow graph for {}", &self.function.name);
-            ControlFlowGraph::new(self.function)
+    /// Get a reference to the `Function` which the `FunctionAnalysis` was
+    /// created with.
+    pub fn function(&self) -> &'m Function {
+        self.function
+    }
+
+    /// Get the custom analysis `A` for the function, computing it with
+    /// `A::compute()` if it hasn't been computed yet. See
+    /// [`FunctionAnalysisPass`](trait.FunctionAnalysisPass.html).
+    pub fn analysis<A: FunctionAnalysisPass>(&self) -> Arc<A> {
+        self.custom_analyses.get_or_insert_with(|| {
+            debug!(
+                "computing custom analysis {} for function {:?}",
+                std::any::type_name::<A>(),
+                &self.function.name
+            );
+            A::compute(self)
         })
     }
 
+    /// Discard all the cached analyses of the function, including custom
+    /// analyses, so that they will be recomputed the next time they are
+    /// requested.
+    ///
+    /// Note that while a `FunctionAnalysis` exists, the `Function` it borrows
+    /// can't be modified; to analyze a modified `Function`, create a new
+    /// `FunctionAnalysis`. This method is useful, e.g., for custom analyses
+    /// which depend on state outside of the `Function`, or to free memory.
+    pub fn invalidate(&mut self) {
+        debug!(
+            "invalidating analyses of function {:?}",
+            &self.function.name
+        );
+        self.control_flow_graph
+            .iter_mut()
+            .for_each(SimpleCache::clear);
+        self.dominator_tree.iter_mut().for_each(SimpleCache::clear);
+        self.postdominator_tree
+            .iter_mut()
+            .for_each(SimpleCache::clear);
+        self.control_dep_graph
+            .iter_mut()
+            .for_each(SimpleCache::clear);
+        self.loop_info.iter_mut().for_each(SimpleCache::clear);
+        self.liveness.iter_mut().for_each(SimpleCache::clear);
+        self.def_use_info.clear();
+        self.data_dep_graph
+            .iter_mut()
+            .flat_map(PerDependenceOptions::iter_mut)
+            .for_each(SimpleCache::clear);
+        self.program_dep_graph
+            .iter_mut()
+            .flat_map(PerDependenceOptions::iter_mut)
+            .for_each(SimpleCache::clear);
+        self.custom_analyses.clear();
+    }
+
+    /// Get the `ControlFlowGraph` for the function.
+    ///
+    /// This uses `ExceptionalFlow::MergeWithReturn`; see
+    /// `control_flow_graph_with()` for other options.
+    pub fn control_flow_graph(&self) -> &ControlFlowGraph<'m> {
+        self.control_flow_graph_with(ExceptionalFlow::MergeWithReturn)
+    }
+
+    /// Get the `ControlFlowGraph` for the function, representing exceptional
+    /// control flow as specified by `exceptional_flow`.
+    pub fn control_flow_graph_with(
+        &self,
+        exceptional_flow: ExceptionalFlow,
+    ) -> &ControlFlowGraph<'m> {
+        self.control_flow_graph
+            .get(exceptional_flow)
+            .get_or_insert_with(|| {
+                debug!(
+                    "computing control flow graph ({:?}) for {}",
+                    exceptional_flow, &self.function.name
+                );
+                ControlFlowGraph::new(self.function, exceptional_flow)
+            })
+    }
+
     /// Get the `DominatorTree` for the function.
-    pub fn dominator_tree(&self) -> Ref<DominatorTree<'m>> {
-        self.dominator_tree.get_or_insert_with(|| {
-            let cfg = self.control_flow_graph();
-            debug!("computing dominator tree for {}", &self.function.name);
-            DominatorTree::new(&cfg)
-        })
+    ///
+    /// This uses `ExceptionalFlow::MergeWithReturn`; see
+    /// `dominator_tree_with()` for other options.
+    pub fn dominator_tree(&self) -> &DominatorTree<'m> {
+        self.dominator_tree_with(ExceptionalFlow::MergeWithReturn)
+    }
+
+    /// Get the `DominatorTree` for the function, computed from the
+    /// `ControlFlowGraph` with the given `ExceptionalFlow`.
+    pub fn dominator_tree_with(&self, exceptional_flow: ExceptionalFlow) -> &DominatorTree<'m> {
+        self.dominator_tree
+            .get(exceptional_flow)
+            .get_or_insert_with(|| {
+                let cfg = self.control_flow_graph_with(exceptional_flow);
+                debug!(
+                    "computing dominator tree ({:?}) for {}",
+                    exceptional_flow, &self.function.name
+                );
+                DominatorTree::new(cfg)
+            })
     }
 
     /// Get the `PostDominatorTree` for the function.
-    pub fn postdominator_tree(&self) -> Ref<PostDominatorTree<'m>> {
-        self.postdominator_tree.get_or_insert_with(|| {
-            let cfg = self.control_flow_graph();
-            debug!("computing postdominator tree for {}", &self.function.name);
-            PostDominatorTree::new(&cfg)
-        })
+    ///
+    /// This uses `ExceptionalFlow::MergeWithReturn`; see
+    /// `postdominator_tree_with()` for other options.
+    pub fn postdominator_tree(&self) -> &PostDominatorTree<'m> {
+        self.postdominator_tree_with(ExceptionalFlow::MergeWithReturn)
+    }
+
+    /// Get the `PostDominatorTree` for the function, computed from the
+    /// `ControlFlowGraph` with the given `ExceptionalFlow`.
+    pub fn postdominator_tree_with(
+        &self,
+        exceptional_flow: ExceptionalFlow,
+    ) -> &PostDominatorTree<'m> {
+        self.postdominator_tree
+            .get(exceptional_flow)
+            .get_or_insert_with(|| {
+                let cfg = self.control_flow_graph_with(exceptional_flow);
+                debug!(
+                    "computing postdominator tree ({:?}) for {}",
+                    exceptional_flow, &self.function.name
+                );
+                PostDominatorTree::new(cfg)
+            })
     }
 
     /// Get the `ControlDependenceGraph` for the function.
-    pub fn control_dependence_graph(&self) -> Ref<ControlDependenceGraph<'m>> {
-        self.control_dep_graph.get_or_insert_with(|| {
-            let cfg = self.control_flow_graph();
-            let postdomtree = self.postdominator_tree();
+    ///
+    /// This uses `ExceptionalFlow::MergeWithReturn`; see
+    /// `control_dependence_graph_with()` for other options.
+    pub fn control_dependence_graph(&self) -> &ControlDependenceGraph<'m> {
+        self.control_dependence_graph_with(ExceptionalFlow::MergeWithReturn)
+    }
+
+    /// Get the `ControlDependenceGraph` for the function, computed from the
+    /// `ControlFlowGraph` with the given `ExceptionalFlow`.
+    pub fn control_dependence_graph_with(
+        &self,
+        exceptional_flow: ExceptionalFlow,
+    ) -> &ControlDependenceGraph<'m> {
+        self.control_dep_graph
+            .get(exceptional_flow)
+            .get_or_insert_with(|| {
+                let cfg = self.control_flow_graph_with(exceptional_flow);
+                let postdomtree = self.postdominator_tree_with(exceptional_flow);
+                debug!(
+                    "computing control dependence graph ({:?}) for {}",
+                    exceptional_flow, &self.function.name
+                );
+                ControlDependenceGraph::new(cfg, postdomtree)
+            })
+    }
+
+    /// Get the `LoopInfo` for the function.
+    ///
+    /// This uses `ExceptionalFlow::MergeWithReturn`; see `loop_info_with()`
+    /// for other options.
+    pub fn loop_info(&self) -> &LoopInfo<'m> {
+        self.loop_info_with(ExceptionalFlow::MergeWithReturn)
+    }
+
+    /// Get the `LoopInfo` for the function, computed from the
+    /// `ControlFlowGraph` with the given `ExceptionalFlow`.
+    pub fn loop_info_with(&self, exceptional_flow: ExceptionalFlow) -> &LoopInfo<'m> {
+        self.loop_info.get(exceptional_flow).get_or_insert_with(|| {
+            let cfg = self.control_flow_graph_with(exceptional_flow);
+            let domtree = self.dominator_tree_with(exceptional_flow);
             debug!(
-                "computing control dependence graph for {}",
-                &self.function.name
+                "computing loop info ({:?}) for {}",
+                exceptional_flow, &self.function.name
+            );
+            LoopInfo::new(cfg, domtree)
+        })
+    }
+
+    /// Get the `Liveness` of SSA values in the function.
+    ///
+    /// This uses `ExceptionalFlow::MergeWithReturn`; see `liveness_with()`
+    /// for other options.
+    pub fn liveness(&self) -> &Liveness<'m> {
+        self.liveness_with(ExceptionalFlow::MergeWithReturn)
+    }
+
+    /// Get the `Liveness` of SSA values in the function, computed from the
+    /// `ControlFlowGraph` with the given `ExceptionalFlow`.
+    pub fn liveness_with(&self, exceptional_flow: ExceptionalFlow) -> &Liveness<'m> {
+        self.liveness.get(exceptional_flow).get_or_insert_with(|| {
+            let cfg = self.control_flow_graph_with(exceptional_flow);
+            debug!(
+                "computing liveness ({:?}) for {}",
+                exceptional_flow, &self.function.name
             );
-            ControlDependenceGraph::new(&cfg, &postdomtree)
+            Liveness::new(cfg)
+        })
+    }
+
+    /// Get the `DefUseInfo` for the function
+    pub fn def_use_info(&self) -> &DefUseInfo<'m> {
+        self.def_use_info.get_or_insert_with(|| {
+            debug!("computing def-use info for {}", &self.function.name);
+            DefUseInfo::new(self.function)
         })
     }
+
+    /// Get the `DataDependenceGraph` for the function, including memory
+    /// dependences.
+    ///
+    /// This uses `ExceptionalFlow::MergeWithReturn` and the default
+    /// `DependenceOptions`; see `data_dependence_graph_with()` for other
+    /// options.
+    pub fn data_dependence_graph(&self) -> &DataDependenceGraph<'m> {
+        self.data_dependence_graph_with(
+            ExceptionalFlow::MergeWithReturn,
+            DependenceOptions::default(),
+        )
+    }
+
+    /// Get the `DataDependenceGraph` for the function, built with the given
+    /// `DependenceOptions`. Memory dependences, if included, are computed
+    /// from the `ControlFlowGraph` with the given `ExceptionalFlow`.
+    pub fn data_dependence_graph_with(
+        &self,
+        exceptional_flow: ExceptionalFlow,
+        options: DependenceOptions,
+    ) -> &DataDependenceGraph<'m> {
+        self.data_dep_graph
+            .get(exceptional_flow)
+            .get(options)
+            .get_or_insert_with(|| {
+                let cfg = self.control_flow_graph_with(exceptional_flow);
+                let def_use = self.def_use_info();
+                debug!(
+                    "computing data dependence graph ({:?}, {:?}) for {}",
+                    exceptional_flow, options, &self.function.name
+                );
+                DataDependenceGraph::new(cfg, def_use, options)
+            })
+    }
+
+    /// Get the `ProgramDependenceGraph` for the function, including memory
+    /// dependences.
+    ///
+    /// This uses `ExceptionalFlow::MergeWithReturn` and the default
+    /// `DependenceOptions`; see `program_dependence_graph_with()` for other
+    /// options.
+    pub fn program_dependence_graph(&self) -> &ProgramDependenceGraph<'m> {
+        self.program_dependence_graph_with(
+            ExceptionalFlow::MergeWithReturn,
+            DependenceOptions::default(),
+        )
+    }
+
+    /// Get the `ProgramDependenceGraph` for the function, computed from the
+    /// `ControlFlowGraph` with the given `ExceptionalFlow`, and with the data
+    /// dependences given by the `DependenceOptions`.
+    pub fn program_dependence_graph_with(
+        &self,
+        exceptional_flow: ExceptionalFlow,
+        options: DependenceOptions,
+    ) -> &ProgramDependenceGraph<'m> {
+        self.program_dep_graph
+            .get(exceptional_flow)
+            .get(options)
+            .get_or_insert_with(|| {
+                let ddg = self.data_dependence_graph_with(exceptional_flow, options);
+                let cdg = self.control_dependence_graph_with(exceptional_flow);
+                debug!(
+                    "computing program dependence graph ({:?}, {:?}) for {}",
+                    exceptional_flow, options, &self.function.name
+                );
+                ProgramDependenceGraph::new(ddg, cdg)
+            })
+    }
+
+    /// Eagerly compute the given analyses for the function, so that later
+    /// requests for them are just cache lookups. Analyses which the given
+    /// ones depend on (e.g., the `ControlFlowGraph`) are computed too.
+    ///
+    /// This uses `ExceptionalFlow::MergeWithReturn`; see `precompute_with()`
+    /// for other options.
+    pub fn precompute(&self, kinds: &[AnalysisKind]) {
+        self.precompute_with(kinds, ExceptionalFlow::MergeWithReturn)
+    }
+
+    /// Eagerly compute the given analyses for the function, representing
+    /// exceptional control flow as specified by `exceptional_flow`. See notes
+    /// on `precompute()`.
+    pub fn precompute_with(&self, kinds: &[AnalysisKind], exceptional_flow: ExceptionalFlow) {
+        for kind in kinds {
+            match kind {
+                AnalysisKind::ControlFlowGraph => {
+                    self.control_flow_graph_with(exceptional_flow);
+                }
+                AnalysisKind::DominatorTree => {
+                    self.dominator_tree_with(exceptional_flow);
+                }
+                AnalysisKind::PostDominatorTree => {
+                    self.postdominator_tree_with(exceptional_flow);
+                }
+                AnalysisKind::ControlDependenceGraph => {
+                    self.control_dependence_graph_with(exceptional_flow);
+                }
+                AnalysisKind::LoopInfo => {
+                    self.loop_info_with(exceptional_flow);
+                }
+                AnalysisKind::Liveness => {
+                    self.liveness_with(exceptional_flow);
+                }
+                AnalysisKind::DefUseInfo => {
+                    self.def_use_info();
+                }
+                AnalysisKind::DataDependenceGraph => {
+                    self.data_dependence_graph_with(exceptional_flow, DependenceOptions::default());
+                }
+                AnalysisKind::ProgramDependenceGraph => {
+                    self.program_dependence_graph_with(
+                        exceptional_flow,
+                        DependenceOptions::default(),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Has the given analysis already been computed for the function (e.g.,
+    /// by `precompute()`), so that requesting it is just a cache lookup?
+    ///
+    /// For the `DataDependenceGraph` and `ProgramDependenceGraph`, this is
+    /// with the default `DependenceOptions`, which `precompute()` uses.
+    ///
+    /// This uses `ExceptionalFlow::MergeWithReturn`; see `is_computed_with()`
+    /// for other options.
+    pub fn is_computed(&self, kind: AnalysisKind) -> bool {
+        self.is_computed_with(kind, ExceptionalFlow::MergeWithReturn)
+    }
+
+    /// Has the given analysis already been computed for the function, with
+    /// exceptional control flow represented as specified by
+    /// `exceptional_flow`? See notes on `is_computed()`.
+    pub fn is_computed_with(&self, kind: AnalysisKind, exceptional_flow: ExceptionalFlow) -> bool {
+        match kind {
+            AnalysisKind::ControlFlowGraph => {
+                self.control_flow_graph.get(exceptional_flow).is_computed()
+            }
+            AnalysisKind::DominatorTree => self.dominator_tree.get(exceptional_flow).is_computed(),
+            AnalysisKind::PostDominatorTree => {
+                self.postdominator_tree.get(exceptional_flow).is_computed()
+            }
+            AnalysisKind::ControlDependenceGraph => {
+                self.control_dep_graph.get(exceptional_flow).is_computed()
+            }
+            AnalysisKind::LoopInfo => self.loop_info.get(exceptional_flow).is_computed(),
+            AnalysisKind::Liveness => self.liveness.get(exceptional_flow).is_computed(),
+            AnalysisKind::DefUseInfo => self.def_use_info.is_computed(),
+            AnalysisKind::DataDependenceGraph => self
+                .data_dep_graph
+                .get(exceptional_flow)
+                .get(DependenceOptions::default())
+                .is_computed(),
+            AnalysisKind::ProgramDependenceGraph => self
+                .program_dep_graph
+                .get(exceptional_flow)
+                .get(DependenceOptions::default())
+                .is_computed(),
+        }
+    }
+}
+
+/// The per-function analyses which can be eagerly computed with
+/// `precompute()`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum AnalysisKind {
+    /// The `ControlFlowGraph`
+    ControlFlowGraph,
+    /// The `DominatorTree`
+    DominatorTree,
+    /// The `PostDominatorTree`
+    PostDominatorTree,
+    /// The `ControlDependenceGraph`
+    ControlDependenceGraph,
+    /// The `LoopInfo`
+    LoopInfo,
+    /// The `Liveness`
+    Liveness,
+    /// The `DefUseInfo`, which doesn't depend on the `ExceptionalFlow`
+    DefUseInfo,
+    /// The `DataDependenceGraph`
+    DataDependenceGraph,
+    /// The `ProgramDependenceGraph`
+    ProgramDependenceGraph,
+}
+
+impl AnalysisKind {
+    /// All the `AnalysisKind`s
+    pub const ALL: &'static [AnalysisKind] = &[
+        AnalysisKind::ControlFlowGraph,
+        AnalysisKind::DominatorTree,
+        AnalysisKind::PostDominatorTree,
+        AnalysisKind::ControlDependenceGraph,
+        AnalysisKind::LoopInfo,
+        AnalysisKind::Liveness,
+        AnalysisKind::DefUseInfo,
+        AnalysisKind::DataDependenceGraph,
+        AnalysisKind::ProgramDependenceGraph,
+    ];
+}
+
+/// Holds one `T` for each `ExceptionalFlow`
+struct PerExceptionalFlow<T> {
+    merge_with_return: T,
+    separate_unwind: T,
+    omit: T,
+}
+
+impl<T> PerExceptionalFlow<T> {
+    fn new(f: impl Fn() -> T) -> Self {
+        Self {
+            merge_with_return: f(),
+            separate_unwind: f(),
+            omit: f(),
+        }
+    }
+
+    fn get(&self, exceptional_flow: ExceptionalFlow) -> &T {
+        match exceptional_flow {
+            ExceptionalFlow::MergeWithReturn => &self.merge_with_return,
+            ExceptionalFlow::SeparateUnwind => &self.separate_unwind,
+            ExceptionalFlow::Omit => &self.omit,
+        }
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        vec![
+            &mut self.merge_with_return,
+            &mut self.separate_unwind,
+            &mut self.omit,
+        ]
+        .into_iter()
+    }
+}
+
+/// The functions of a `Module` which are being analyzed: the `Module`'s own
+/// functions, except for any replaced with `ModuleAnalysis::replace_function()`
+#[derive(Clone, Copy)]
+pub(crate) struct ModuleFunctions<'m, 'a> {
+    /// The `Module`, for its types, declarations, and global variables
+    pub(crate) module: &'m Module,
+    /// The functions, in place of the `Module`'s own `functions`
+    pub(crate) functions: &'a [&'m Function],
+}
+
+/// Holds one `T` for each `IndirectCallTargets`
+struct PerIndirectCallTargets<T> {
+    address_taken: T,
+    matching_type: T,
+}
+
+impl<T> PerIndirectCallTargets<T> {
+    fn new(f: impl Fn() -> T) -> Self {
+        Self {
+            address_taken: f(),
+            matching_type: f(),
+        }
+    }
+
+    fn get(&self, indirect_call_targets: IndirectCallTargets) -> &T {
+        match indirect_call_targets {
+            IndirectCallTargets::AddressTaken => &self.address_taken,
+            IndirectCallTargets::MatchingType => &self.matching_type,
+        }
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        vec![&mut self.address_taken, &mut self.matching_type].into_iter()
+    }
+}
+
+/// Holds one `T` for each `DependenceOptions`. Any option added to
+/// `DependenceOptions` needs its own slots here.
+struct PerDependenceOptions<T> {
+    with_memory: T,
+    without_memory: T,
+}
+
+impl<T> PerDependenceOptions<T> {
+    fn new(f: impl Fn() -> T) -> Self {
+        Self {
+            with_memory: f(),
+            without_memory: f(),
+        }
+    }
+
+    fn get(&self, options: DependenceOptions) -> &T {
+        if options.memory {
+            &self.with_memory
+        } else {
+            &self.without_memory
+        }
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        vec![&mut self.with_memory, &mut self.without_memory].into_iter()
+    }
 }
 
 struct SimpleCache<T> {
-    /// `None` if not computed yet
-    data: RefCell<Option<T>>,
+    /// Empty if not computed yet
+    data: OnceCell<T>,
 }
 
 impl<T> SimpleCache<T> {
     fn new() -> Self {
         Self {
-            data: RefCell::new(None),
+            data: OnceCell::new(),
         }
     }
 
     /// Get the cached value, or if no value is cached, compute the value using
-    /// the given closure, then cache that result and return it
-    fn get_or_insert_with(&self, f: impl FnOnce() -> T) -> Ref<T> {
-        // borrow mutably only if it's empty. else don't even try to borrow mutably
-        let need_mutable_borrow = self.data.borrow().is_none();
-        if need_mutable_borrow {
-            let old_val = self.data.borrow_mut().replace(f());
-            debug_assert!(old_val.is_none());
-        }
-        // now, either way, it's populated, so we borrow immutably and return.
-        // future users can also borrow immutably using this function (even
-        // while this borrow is still outstanding), since it won't try to borrow
-        // mutably in the future.
-        Ref::map(self.data.borrow(), |o| {
-            o.as_ref().expect("should be populated now")
-        })
+    /// the given closure, then cache that result and return it.
+    ///
+    /// If several threads call this at once on an empty cache, only one of
+    /// them computes the value; the others block until it is available.
+    fn get_or_insert_with(&self, f: impl FnOnce() -> T) -> &T {
+        self.data.get_or_init(f)
+    }
+
+    /// Is a value cached?
+    fn is_computed(&self) -> bool {
+        self.data.get().is_some()
+    }
+
+    /// Discard the cached value, if any, so that it will be recomputed the
+    /// next time it is requested
+    fn clear(&mut self) {
+        self.data.take();
     }
 }
@@ -0,0 +1,220 @@
+use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
+use crate::dataflow::{DataflowAnalysis, DataflowDirection};
+use crate::operands::{instruction_operands, terminator_operands};
+use llvm_ir::{BasicBlock, Function, Instruction, Name, Operand, Terminator};
+use std::collections::{HashMap, HashSet};
+
+/// Liveness of SSA values (instruction results and function parameters) in a
+/// particular function: which values may still be used later, at each point
+/// in the function.
+///
+/// Operands of `Phi` instructions are considered to be used on the edge from
+/// the corresponding predecessor; so they are live-out of that predecessor,
+/// but not (on account of the `Phi`) live-in to the block containing the
+/// `Phi`. Likewise, the result of a `Phi` is defined at the start of its block,
+/// so it is not live-in to the block.
+///
+/// Unreachable blocks have no live values.
+///
+/// To construct a `Liveness`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct Liveness<'m> {
+    /// Map from block name to the values live at the start of that block
+    live_in: HashMap<&'m Name, HashSet<&'m Name>>,
+
+    /// Map from block name to the values live at the end of that block
+    live_out: HashMap<&'m Name, HashSet<&'m Name>>,
+
+    /// The function itself
+    function: &'m Function,
+}
+
+impl<'m> Liveness<'m> {
+    pub(crate) fn new(cfg: &ControlFlowGraph<'m>) -> Self {
+        let function = cfg.function;
+        let analysis = LivenessAnalysis {
+            blocks: function
+                .basic_blocks
+                .iter()
+                .map(|bb| (&bb.name, bb))
+                .collect(),
+        };
+        let result = cfg.solve_dataflow(&analysis);
+        let mut live_in = HashMap::new();
+        let mut live_out = HashMap::new();
+        for block in cfg.reachable_blocks() {
+            if let Some(fact) = result.fact_in(block) {
+                live_in.insert(block, fact.clone());
+            }
+            if let Some(fact) = result.fact_out(block) {
+                live_out.insert(block, fact.clone());
+            }
+        }
+        Self {
+            live_in,
+            live_out,
+            function,
+        }
+    }
+
+    /// Iterate over the values which are live at the start of the basic block
+    /// with the given `Name`
+    pub fn live_in<'s>(&'s self, block: &'m Name) -> impl Iterator<Item = &'m Name> + 's {
+        self.live_in.get(block).into_iter().flatten().copied()
+    }
+
+    /// Iterate over the values which are live at the end of the basic block
+    /// with the given `Name`, including values used by `Phi`s in its
+    /// successors along the edges from this block
+    pub fn live_out<'s>(&'s self, block: &'m Name) -> impl Iterator<Item = &'m Name> + 's {
+        self.live_out.get(block).into_iter().flatten().copied()
+    }
+
+    /// Is the value with the given `Name` live at the start of the basic block
+    /// with the given `Name`?
+    pub fn is_live_in(&self, value: &Name, block: &'m Name) -> bool {
+        matches!(self.live_in.get(block), Some(live) if live.contains(value))
+    }
+
+    /// Is the value with the given `Name` live at the end of the basic block
+    /// with the given `Name`?
+    pub fn is_live_out(&self, value: &Name, block: &'m Name) -> bool {
+        matches!(self.live_out.get(block), Some(live) if live.contains(value))
+    }
+
+    /// Get the values which are live immediately after the instruction at the
+    /// given `index` in the basic block with the given `Name`. An `index`
+    /// equal to the number of instructions in the block refers to the
+    /// terminator, so this gives the same values as `live_out()`.
+    ///
+    /// This walks the block backwards from the end; to get the values live
+    /// after every instruction in a block, `live_sets()` is more efficient.
+    ///
+    /// Returns `None` if the block doesn't exist, or if `index` is out of
+    /// range. If the block is unreachable, the set is empty.
+    pub fn live_after(&self, block: &'m Name, index: usize) -> Option<HashSet<&'m Name>> {
+        let bb = self.function.get_bb_by_name(block)?;
+        if index > bb.instrs.len() {
+            return None;
+        }
+        let mut live = match self.live_out.get(block) {
+            Some(live) => live.clone(),
+            None => return Some(HashSet::new()),
+        };
+        if index < bb.instrs.len() {
+            transfer_terminator(&bb.term, &mut live);
+            for inst in bb.instrs[index + 1..].iter().rev() {
+                transfer_instruction(inst, &mut live);
+            }
+        }
+        Some(live)
+    }
+
+    /// Is the value with the given `Name` live immediately after the
+    /// instruction at the given `index` in the basic block with the given
+    /// `Name`? See notes on `live_after()`.
+    ///
+    /// Returns `false` if the block doesn't exist, or if `index` is out of
+    /// range.
+    pub fn is_live_after(&self, value: &Name, block: &'m Name, index: usize) -> bool {
+        matches!(self.live_after(block, index), Some(live) if live.contains(value))
+    }
+
+    /// Get the values which are live immediately after each instruction in the
+    /// basic block with the given `Name`, in a single backward pass over the
+    /// block. The set at position `i` is the same as `live_after(block, i)`;
+    /// the last set, after the terminator, is the same as `live_out()`.
+    ///
+    /// Returns `None` if the block doesn't exist. If the block is unreachable,
+    /// the sets are all empty.
+    pub fn live_sets(&self, block: &'m Name) -> Option<Vec<HashSet<&'m Name>>> {
+        let bb = self.function.get_bb_by_name(block)?;
+        let mut live = match self.live_out.get(block) {
+            Some(live) => live.clone(),
+            None => return Some(vec![HashSet::new(); bb.instrs.len() + 1]),
+        };
+        let mut sets = Vec::with_capacity(bb.instrs.len() + 1);
+        sets.push(live.clone());
+        transfer_terminator(&bb.term, &mut live);
+        for inst in bb.instrs.iter().rev() {
+            sets.push(live.clone());
+            transfer_instruction(inst, &mut live);
+        }
+        sets.reverse();
+        Some(sets)
+    }
+}
+
+/// The `DataflowAnalysis` computing liveness
+struct LivenessAnalysis<'m> {
+    /// Map from block name to the block, for finding `Phi`s in successors
+    blocks: HashMap<&'m Name, &'m BasicBlock>,
+}
+
+impl<'m> DataflowAnalysis<'m> for LivenessAnalysis<'m> {
+    type Fact = HashSet<&'m Name>;
+    const DIRECTION: DataflowDirection = DataflowDirection::Backward;
+
+    fn bottom(&self) -> Self::Fact {
+        HashSet::new()
+    }
+
+    fn join(&self, fact: &mut Self::Fact, other: &Self::Fact) {
+        fact.extend(other.iter().copied());
+    }
+
+    fn transfer_instruction(&self, inst: &'m Instruction, fact: &mut Self::Fact) {
+        transfer_instruction(inst, fact)
+    }
+
+    fn transfer_terminator(&self, term: &'m Terminator, fact: &mut Self::Fact) {
+        transfer_terminator(term, fact)
+    }
+
+    fn transfer_edge(&self, from: &'m Name, to: CFGNode<'m>, fact: &mut Self::Fact) {
+        if let CFGNode::Block(to) = to {
+            for inst in &self.blocks[to].instrs {
+                match inst {
+                    Instruction::Phi(phi) => {
+                        for (value, pred) in &phi.incoming_values {
+                            if pred == from {
+                                if let Operand::LocalOperand { name, .. } = value {
+                                    fact.insert(name);
+                                }
+                            }
+                        }
+                    }
+                    _ => break, // `Phi`s are always at the start of the block
+                }
+            }
+        }
+    }
+}
+
+/// Update the live values `live` from after the given instruction to before
+/// it. `Phi` operands are not added; see notes on `Liveness`.
+fn transfer_instruction<'m>(inst: &'m Instruction, live: &mut HashSet<&'m Name>) {
+    if let Some(result) = inst.try_get_result() {
+        live.remove(result);
+    }
+    if !matches!(inst, Instruction::Phi(_)) {
+        add_local_operands(instruction_operands(inst), live);
+    }
+}
+
+/// Update the live values `live` from after the given terminator to before it
+fn transfer_terminator<'m>(term: &'m Terminator, live: &mut HashSet<&'m Name>) {
+    if let Some(result) = term.try_get_result() {
+        live.remove(result);
+    }
+    add_local_operands(terminator_operands(term), live);
+}
+
+fn add_local_operands<'m>(operands: Vec<&'m Operand>, live: &mut HashSet<&'m Name>) {
+    for operand in operands {
+        if let Operand::LocalOperand { name, .. } = operand {
+            live.insert(name);
+        }
+    }
+}
@@ -0,0 +1,482 @@
+use crate::control_flow_graph::{CFGNode, ControlFlowGraph};
+use crate::dominator_tree::DominatorTree;
+use llvm_ir::Name;
+use petgraph::algo::tarjan_scc;
+use petgraph::prelude::DiGraphMap;
+use std::collections::{HashMap, HashSet};
+
+/// The natural loops of a particular function, and how they are nested.
+///
+/// Each loop is identified by its header block. If several back edges go to
+/// the same header, they all belong to a single loop. Two distinct natural
+/// loops are either disjoint or one is nested inside the other, so the loops
+/// form a forest.
+///
+/// Loops in unreachable code are not detected.
+///
+/// Cycles in irreducible control flow (cycles which can be entered at more
+/// than one block) are not natural loops, so they aren't reported as `Loop`s.
+/// Use `is_reducible()`, `irreducible_edges()`, and `irreducible_regions()` to
+/// detect them.
+///
+/// To construct a `LoopInfo`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct LoopInfo<'m> {
+    /// All the loops in the function. Every loop appears after the loop it is
+    /// nested in (if any).
+    loops: Vec<Loop<'m>>,
+
+    /// Map from a block to the index (in `loops`) of the innermost loop
+    /// containing it. Blocks which aren't in any loop won't be in this map.
+    innermost: HashMap<&'m Name, usize>,
+
+    /// Map from a loop header to the index (in `loops`) of its loop
+    by_header: HashMap<&'m Name, usize>,
+
+    /// Retreating edges (in a depth-first traversal from the entry) which
+    /// aren't back edges, as (source, destination) pairs
+    irreducible_edges: Vec<(&'m Name, &'m Name)>,
+
+    /// Strongly connected regions which can be entered at more than one block
+    irreducible_regions: Vec<IrreducibleRegion<'m>>,
+}
+
+/// A single natural loop. See [`LoopInfo`](struct.LoopInfo.html).
+pub struct Loop<'m> {
+    /// The loop header, i.e., the single entry block of the loop. It dominates
+    /// every block in the loop.
+    header: &'m Name,
+
+    /// All blocks in the loop, including the header and the blocks of any
+    /// nested loops
+    blocks: HashSet<&'m Name>,
+
+    /// Blocks in the loop which have a back edge to the header
+    latches: Vec<&'m Name>,
+
+    /// Blocks in the loop which have a successor outside the loop
+    exiting_blocks: Vec<&'m Name>,
+
+    /// Successors of blocks in the loop which are outside the loop
+    exit_blocks: Vec<CFGNode<'m>>,
+
+    /// See notes on `Loop::preheader()`
+    preheader: Option<&'m Name>,
+
+    /// Nesting depth: 1 for an outermost loop, 2 for a loop directly inside
+    /// it, etc
+    depth: usize,
+
+    /// Index (in `LoopInfo.loops`) of the loop this loop is directly nested in
+    parent: Option<usize>,
+
+    /// Indices (in `LoopInfo.loops`) of the loops directly nested in this loop
+    children: Vec<usize>,
+}
+
+/// A region of irreducible control flow, i.e., a strongly connected set of
+/// blocks which can be entered at more than one block.
+/// See [`LoopInfo::irreducible_regions()`](struct.LoopInfo.html#method.irreducible_regions).
+pub struct IrreducibleRegion<'m> {
+    /// All blocks in the region, sorted
+    blocks: Vec<&'m Name>,
+
+    /// Blocks in the region which can be entered from outside it, sorted
+    entries: Vec<&'m Name>,
+}
+
+impl<'m> LoopInfo<'m> {
+    pub(crate) fn new(cfg: &ControlFlowGraph<'m>, domtree: &DominatorTree<'m>) -> Self {
+        // Find the back edges, i.e., edges whose destination dominates their
+        // source, grouped by header. Every node dominates itself, so we skip
+        // edges from unreachable blocks, which would otherwise make
+        // unreachable self-loops look like loops.
+        let mut latches_by_header: HashMap<&'m Name, Vec<&'m Name>> = HashMap::new();
+        for (from, to, _) in cfg.graph.all_edges() {
+            if let (CFGNode::Block(latch), CFGNode::Block(header)) = (from, to) {
+                if domtree.dominates(cfg.entry_node, from) && domtree.dominates(to, from) {
+                    latches_by_header.entry(header).or_default().push(latch);
+                }
+            }
+        }
+
+        // The body of the loop for a header is the header plus everything
+        // which can reach one of its latches without going through the header
+        let mut loops: Vec<Loop<'m>> = latches_by_header
+            .into_iter()
+            .map(|(header, mut latches)| {
+                latches.sort();
+                let mut blocks: HashSet<&'m Name> = HashSet::new();
+                blocks.insert(header);
+                let mut worklist = latches.clone();
+                while let Some(block) = worklist.pop() {
+                    if blocks.insert(block) {
+                        // only consider reachable preds; unreachable blocks
+                        // can branch into the loop body without being part of
+                        // it
+                        worklist.extend(cfg.preds(block).filter(|&pred| {
+                            domtree.dominates(cfg.entry_node, CFGNode::Block(pred))
+                        }));
+                    }
+                }
+                Loop {
+                    header,
+                    blocks,
+                    latches,
+                    exiting_blocks: vec![],
+                    exit_blocks: vec![],
+                    preheader: None,
+                    depth: 0,
+                    parent: None,
+                    children: vec![],
+                }
+            })
+            .collect();
+
+        // An enclosing loop is strictly larger than the loops nested inside
+        // it, so sorting by decreasing size puts every loop after its parent
+        loops.sort_by(|a, b| {
+            b.blocks
+                .len()
+                .cmp(&a.blocks.len())
+                .then_with(|| a.header.cmp(b.header))
+        });
+
+        // Visiting loops outermost-first, the last loop we see containing a
+        // block is the innermost one
+        let mut innermost: HashMap<&'m Name, usize> = HashMap::new();
+        for (idx, l) in loops.iter().enumerate() {
+            for &block in &l.blocks {
+                innermost.insert(block, idx);
+            }
+        }
+
+        for idx in 0..loops.len() {
+            // The parent is the innermost of the (larger) loops containing
+            // this loop's header
+            let header = loops[idx].header;
+            let parent = loops[..idx].iter().rposition(|l| l.blocks.contains(header));
+            if let Some(parent) = parent {
+                loops[parent].children.push(idx);
+                loops[idx].depth = loops[parent].depth + 1;
+            } else {
+                loops[idx].depth = 1;
+            }
+            loops[idx].parent = parent;
+
+            let l = &mut loops[idx];
+            let mut exiting_blocks = vec![];
+            let mut exit_blocks = vec![];
+            for &block in &l.blocks {
+                let mut exiting = false;
+                for succ in cfg.succs(block) {
+                    let outside = match succ {
+                        CFGNode::Block(succ) => !l.blocks.contains(succ),
+                        _ => true,
+                    };
+                    if outside {
+                        exiting = true;
+                        if !exit_blocks.contains(&succ) {
+                            exit_blocks.push(succ);
+                        }
+                    }
+                }
+                if exiting {
+                    exiting_blocks.push(block);
+                }
+            }
+            exiting_blocks.sort();
+            exit_blocks.sort();
+            l.exiting_blocks = exiting_blocks;
+            l.exit_blocks = exit_blocks;
+
+            let mut outside_preds = cfg.preds(l.header).filter(|pred| !l.blocks.contains(pred));
+            l.preheader = match (outside_preds.next(), outside_preds.next()) {
+                (Some(pred), None) if cfg.succs(pred).count() == 1 => Some(pred),
+                _ => None,
+            };
+        }
+
+        let by_header = loops
+            .iter()
+            .enumerate()
+            .map(|(idx, l)| (l.header, idx))
+            .collect();
+
+        // A retreating edge goes from a node to one of its ancestors in a
+        // depth-first traversal. The CFG is reducible if and only if every
+        // retreating edge is a back edge.
+        let mut irreducible_edges = vec![];
+        let mut visited = HashSet::new();
+        let mut on_stack = HashSet::new();
+        let mut stack: Vec<(CFGNode<'m>, Vec<CFGNode<'m>>)> = vec![];
+        let succs_of = |node| {
+            let mut succs: Vec<CFGNode<'m>> = cfg.succs_as_nodes(node).collect();
+            succs.reverse();
+            succs
+        };
+        visited.insert(cfg.entry_node);
+        on_stack.insert(cfg.entry_node);
+        stack.push((cfg.entry_node, succs_of(cfg.entry_node)));
+        while let Some((node, succs)) = stack.last_mut() {
+            let node = *node;
+            match succs.pop() {
+                Some(succ) if on_stack.contains(&succ) => {
+                    if !domtree.dominates(succ, node) {
+                        if let (CFGNode::Block(from), CFGNode::Block(to)) = (node, succ) {
+                            irreducible_edges.push((from, to));
+                        }
+                    }
+                }
+                Some(succ) => {
+                    if visited.insert(succ) {
+                        on_stack.insert(succ);
+                        stack.push((succ, succs_of(succ)));
+                    }
+                }
+                None => {
+                    on_stack.remove(&node);
+                    stack.pop();
+                }
+            }
+        }
+        irreducible_edges.sort();
+
+        let irreducible_regions = if irreducible_edges.is_empty() {
+            vec![]
+        } else {
+            find_irreducible_regions(cfg, domtree)
+        };
+
+        Self {
+            loops,
+            innermost,
+            by_header,
+            irreducible_edges,
+            irreducible_regions,
+        }
+    }
+
+    /// Iterate over all the loops in the function, including nested loops.
+    ///
+    /// Every loop is yielded after the loop it is nested in (if any).
+    pub fn loops<'s>(&'s self) -> impl Iterator<Item = &'s Loop<'m>> + 's {
+        self.loops.iter()
+    }
+
+    /// Iterate over the outermost loops in the function, i.e., the loops which
+    /// aren't nested in any other loop
+    pub fn top_level_loops<'s>(&'s self) -> impl Iterator<Item = &'s Loop<'m>> + 's {
+        self.loops.iter().filter(|l| l.parent.is_none())
+    }
+
+    /// Get the innermost loop containing the basic block with the given
+    /// `Name`, or `None` if the block isn't in any loop
+    pub fn loop_for(&self, block: &'m Name) -> Option<&Loop<'m>> {
+        self.innermost.get(block).map(|&idx| &self.loops[idx])
+    }
+
+    /// Get the loop nesting depth of the basic block with the given `Name`.
+    ///
+    /// This is 0 for blocks which aren't in any loop, 1 for blocks in an
+    /// outermost loop but not in any loop nested inside it, etc.
+    pub fn loop_depth(&self, block: &'m Name) -> usize {
+        self.loop_for(block).map_or(0, |l| l.depth)
+    }
+
+    /// Get the loop whose header is the basic block with the given `Name`, or
+    /// `None` if the block isn't a loop header
+    pub fn loop_with_header(&self, header: &'m Name) -> Option<&Loop<'m>> {
+        self.by_header.get(header).map(|&idx| &self.loops[idx])
+    }
+
+    /// Is the basic block with the given `Name` a loop header?
+    pub fn is_loop_header(&self, block: &'m Name) -> bool {
+        self.by_header.contains_key(block)
+    }
+
+    /// Get the loop which `l` is directly nested in, or `None` if `l` is an
+    /// outermost loop
+    pub fn parent_loop(&self, l: &Loop<'m>) -> Option<&Loop<'m>> {
+        l.parent.map(|idx| &self.loops[idx])
+    }
+
+    /// Iterate over the loops which are directly nested in `l`
+    pub fn subloops<'s>(&'s self, l: &'s Loop<'m>) -> impl Iterator<Item = &'s Loop<'m>> + 's {
+        l.children.iter().map(move |&idx| &self.loops[idx])
+    }
+
+    /// Iterate over the back edges in the function, as (latch, header) pairs
+    pub fn back_edges<'s>(&'s self) -> impl Iterator<Item = (&'m Name, &'m Name)> + 's {
+        self.loops
+            .iter()
+            .flat_map(|l| l.latches.iter().map(move |&latch| (latch, l.header)))
+    }
+
+    /// Is the function's CFG reducible, i.e., is every cycle a natural loop
+    /// with a single entry block?
+    pub fn is_reducible(&self) -> bool {
+        self.irreducible_edges.is_empty()
+    }
+
+    /// Iterate over the edges which make the CFG irreducible, as (source,
+    /// destination) pairs.
+    ///
+    /// These are the retreating edges (edges from a block to one of its
+    /// ancestors in a depth-first traversal from the entry) whose destination
+    /// doesn't dominate their source. Which edges are reported depends on the
+    /// order of the traversal, but this is empty if and only if the CFG is
+    /// reducible.
+    pub fn irreducible_edges<'s>(&'s self) -> impl Iterator<Item = (&'m Name, &'m Name)> + 's {
+        self.irreducible_edges.iter().copied()
+    }
+
+    /// Iterate over the regions of irreducible control flow in the function,
+    /// i.e., the strongly connected sets of blocks which can be entered at
+    /// more than one block.
+    ///
+    /// An irreducible region may be nested inside a natural loop; in that
+    /// case, the loop's header isn't part of the region.
+    pub fn irreducible_regions<'s>(
+        &'s self,
+    ) -> impl Iterator<Item = &'s IrreducibleRegion<'m>> + 's {
+        self.irreducible_regions.iter()
+    }
+}
+
+impl<'m> Loop<'m> {
+    /// Get the `Name` of the loop header
+    pub fn header(&self) -> &'m Name {
+        self.header
+    }
+
+    /// Iterate over the blocks in the loop, including the header and the
+    /// blocks of any nested loops
+    pub fn blocks<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.blocks.iter().copied()
+    }
+
+    /// Is the basic block with the given `Name` in the loop (or in a loop
+    /// nested inside it)?
+    pub fn contains(&self, block: &'m Name) -> bool {
+        self.blocks.contains(block)
+    }
+
+    /// Iterate over the latches of the loop, i.e., the blocks in the loop
+    /// which have a back edge to the header
+    pub fn latches<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.latches.iter().copied()
+    }
+
+    /// Iterate over the exiting blocks of the loop, i.e., the blocks in the
+    /// loop which have a successor outside the loop
+    pub fn exiting_blocks<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.exiting_blocks.iter().copied()
+    }
+
+    /// Iterate over the exit blocks of the loop, i.e., the successors of
+    /// blocks in the loop which are outside the loop.
+    ///
+    /// Here, `CFGNode::Return` (or `CFGNode::Unwind`) indicates that the
+    /// function may return (or unwind) directly from inside the loop.
+    pub fn exit_blocks<'s>(&'s self) -> impl Iterator<Item = CFGNode<'m>> + 's {
+        self.exit_blocks.iter().copied()
+    }
+
+    /// Get the preheader of the loop, if it has one.
+    ///
+    /// The preheader is the unique predecessor of the header from outside the
+    /// loop, provided that the header is its only successor. If the header has
+    /// several predecessors outside the loop, or its predecessor outside the
+    /// loop may branch elsewhere, the loop has no preheader.
+    pub fn preheader(&self) -> Option<&'m Name> {
+        self.preheader
+    }
+
+    /// Get the nesting depth of the loop: 1 for an outermost loop, 2 for a
+    /// loop directly nested inside an outermost loop, etc
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+impl<'m> IrreducibleRegion<'m> {
+    /// Iterate over the blocks in the region
+    pub fn blocks<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.blocks.iter().copied()
+    }
+
+    /// Iterate over the entries of the region, i.e., the blocks in the region
+    /// which can be entered from outside it. There are always at least two.
+    pub fn entries<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        self.entries.iter().copied()
+    }
+}
+
+/// Find the strongly connected regions of the (reachable part of the) CFG
+/// which have more than one entry.
+///
+/// A strongly connected region with a single entry is a natural loop; we look
+/// for irreducible regions inside it by removing its entry and searching the
+/// remaining blocks again.
+fn find_irreducible_regions<'m>(
+    cfg: &ControlFlowGraph<'m>,
+    domtree: &DominatorTree<'m>,
+) -> Vec<IrreducibleRegion<'m>> {
+    let reachable: HashSet<&'m Name> = cfg
+        .graph
+        .nodes()
+        .filter_map(|node| match node {
+            CFGNode::Block(block) if domtree.dominates(cfg.entry_node, node) => Some(block),
+            _ => None,
+        })
+        .collect();
+
+    let mut regions = vec![];
+    let mut worklist = vec![reachable];
+    while let Some(blocks) = worklist.pop() {
+        let mut subgraph: DiGraphMap<&'m Name, ()> = DiGraphMap::new();
+        for &block in &blocks {
+            subgraph.add_node(block);
+            for succ in cfg.succs(block) {
+                if let CFGNode::Block(succ) = succ {
+                    if blocks.contains(succ) {
+                        subgraph.add_edge(block, succ, ());
+                    }
+                }
+            }
+        }
+        for scc in tarjan_scc(&subgraph) {
+            if scc.len() == 1 && !subgraph.contains_edge(scc[0], scc[0]) {
+                continue; // not a cycle
+            }
+            let scc: HashSet<&'m Name> = scc.into_iter().collect();
+            let mut entries: Vec<&'m Name> = scc
+                .iter()
+                .copied()
+                .filter(|&block| {
+                    CFGNode::Block(block) == cfg.entry_node
+                        || cfg.preds(block).any(|pred| {
+                            !scc.contains(pred)
+                                && domtree.dominates(cfg.entry_node, CFGNode::Block(pred))
+                        })
+                })
+                .collect();
+            if entries.len() > 1 {
+                let mut blocks: Vec<&'m Name> = scc.into_iter().collect();
+                blocks.sort();
+                entries.sort();
+                regions.push(IrreducibleRegion { blocks, entries });
+            } else {
+                let mut scc = scc;
+                for entry in entries {
+                    scc.remove(entry);
+                }
+                worklist.push(scc);
+            }
+        }
+    }
+    regions.sort_by(|a, b| a.entries.cmp(&b.entries));
+    regions
+}
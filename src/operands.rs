@@ -0,0 +1,179 @@
+use either::Either;
+use llvm_ir::{Constant, ConstantRef, Instruction, Operand, Terminator};
+
+/// Get all the operands of the given instruction.
+///
+/// For `Call`, this includes the function being called, unless it is inline
+/// assembly.
+pub(crate) fn instruction_operands(inst: &Instruction) -> Vec<&Operand> {
+    match inst {
+        Instruction::Add(i) => vec![&i.operand0, &i.operand1],
+        Instruction::Sub(i) => vec![&i.operand0, &i.operand1],
+        Instruction::Mul(i) => vec![&i.operand0, &i.operand1],
+        Instruction::UDiv(i) => vec![&i.operand0, &i.operand1],
+        Instruction::SDiv(i) => vec![&i.operand0, &i.operand1],
+        Instruction::URem(i) => vec![&i.operand0, &i.operand1],
+        Instruction::SRem(i) => vec![&i.operand0, &i.operand1],
+        Instruction::And(i) => vec![&i.operand0, &i.operand1],
+        Instruction::Or(i) => vec![&i.operand0, &i.operand1],
+        Instruction::Xor(i) => vec![&i.operand0, &i.operand1],
+        Instruction::Shl(i) => vec![&i.operand0, &i.operand1],
+        Instruction::LShr(i) => vec![&i.operand0, &i.operand1],
+        Instruction::AShr(i) => vec![&i.operand0, &i.operand1],
+        Instruction::FAdd(i) => vec![&i.operand0, &i.operand1],
+        Instruction::FSub(i) => vec![&i.operand0, &i.operand1],
+        Instruction::FMul(i) => vec![&i.operand0, &i.operand1],
+        Instruction::FDiv(i) => vec![&i.operand0, &i.operand1],
+        Instruction::FRem(i) => vec![&i.operand0, &i.operand1],
+        Instruction::FNeg(i) => vec![&i.operand],
+        Instruction::ExtractElement(i) => vec![&i.vector, &i.index],
+        Instruction::InsertElement(i) => vec![&i.vector, &i.element, &i.index],
+        Instruction::ShuffleVector(i) => vec![&i.operand0, &i.operand1],
+        Instruction::ExtractValue(i) => vec![&i.aggregate],
+        Instruction::InsertValue(i) => vec![&i.aggregate, &i.element],
+        Instruction::Alloca(i) => vec![&i.num_elements],
+        Instruction::Load(i) => vec![&i.address],
+        Instruction::Store(i) => vec![&i.address, &i.value],
+        Instruction::Fence(_) => vec![],
+        Instruction::CmpXchg(i) => vec![&i.address, &i.expected, &i.replacement],
+        Instruction::AtomicRMW(i) => vec![&i.address, &i.value],
+        Instruction::GetElementPtr(i) => std::iter::once(&i.address)
+            .chain(i.indices.iter())
+            .collect(),
+        Instruction::Trunc(i) => vec![&i.operand],
+        Instruction::ZExt(i) => vec![&i.operand],
+        Instruction::SExt(i) => vec![&i.operand],
+        Instruction::FPTrunc(i) => vec![&i.operand],
+        Instruction::FPExt(i) => vec![&i.operand],
+        Instruction::FPToUI(i) => vec![&i.operand],
+        Instruction::FPToSI(i) => vec![&i.operand],
+        Instruction::UIToFP(i) => vec![&i.operand],
+        Instruction::SIToFP(i) => vec![&i.operand],
+        Instruction::PtrToInt(i) => vec![&i.operand],
+        Instruction::IntToPtr(i) => vec![&i.operand],
+        Instruction::BitCast(i) => vec![&i.operand],
+        Instruction::AddrSpaceCast(i) => vec![&i.operand],
+        Instruction::ICmp(i) => vec![&i.operand0, &i.operand1],
+        Instruction::FCmp(i) => vec![&i.operand0, &i.operand1],
+        Instruction::Phi(i) => i.incoming_values.iter().map(|(op, _)| op).collect(),
+        Instruction::Select(i) => vec![&i.condition, &i.true_value, &i.false_value],
+        #[cfg(feature = "llvm-10-or-greater")]
+        Instruction::Freeze(i) => vec![&i.operand],
+        Instruction::Call(i) => call_operands(&i.function, &i.arguments),
+        Instruction::VAArg(i) => vec![&i.arg_list],
+        Instruction::LandingPad(_) => vec![],
+        Instruction::CatchPad(i) => std::iter::once(&i.catch_switch)
+            .chain(i.args.iter())
+            .collect(),
+        Instruction::CleanupPad(i) => std::iter::once(&i.parent_pad)
+            .chain(i.args.iter())
+            .collect(),
+    }
+}
+
+/// Get all the operands of the given terminator.
+///
+/// For `Invoke` and `CallBr`, this includes the function being called, unless
+/// it is inline assembly.
+pub(crate) fn terminator_operands(term: &Terminator) -> Vec<&Operand> {
+    match term {
+        Terminator::Ret(t) => t.return_operand.iter().collect(),
+        Terminator::Br(_) => vec![],
+        Terminator::CondBr(t) => vec![&t.condition],
+        Terminator::Switch(t) => vec![&t.operand],
+        Terminator::IndirectBr(t) => vec![&t.operand],
+        Terminator::Invoke(t) => call_operands(&t.function, &t.arguments),
+        Terminator::Resume(t) => vec![&t.operand],
+        Terminator::Unreachable(_) => vec![],
+        Terminator::CleanupRet(t) => vec![&t.cleanup_pad],
+        Terminator::CatchRet(t) => vec![&t.catch_pad],
+        Terminator::CatchSwitch(t) => vec![&t.parent_pad],
+        #[cfg(not(feature = "llvm-8"))]
+        Terminator::CallBr(t) => call_operands(&t.function, &t.arguments),
+    }
+}
+
+fn call_operands<'a, T, A>(
+    function: &'a Either<T, Operand>,
+    arguments: &'a [(Operand, A)],
+) -> Vec<&'a Operand> {
+    function
+        .as_ref()
+        .right()
+        .into_iter()
+        .chain(arguments.iter().map(|(op, _)| op))
+        .collect()
+}
+
+/// Get the constants which the given constant is directly built from, e.g.,
+/// the elements of an array, or the operands of a constant expression
+pub(crate) fn constant_operands(constant: &Constant) -> Vec<&ConstantRef> {
+    match constant {
+        Constant::Int { .. }
+        | Constant::Float(_)
+        | Constant::Null(_)
+        | Constant::AggregateZero(_)
+        | Constant::Undef(_)
+        | Constant::BlockAddress
+        | Constant::GlobalReference { .. }
+        | Constant::TokenNone => vec![],
+        #[cfg(feature = "llvm-12-or-greater")]
+        Constant::Poison(_) => vec![],
+        Constant::Struct { values, .. } => values.iter().collect(),
+        Constant::Array { elements, .. } => elements.iter().collect(),
+        Constant::Vector(elements) => elements.iter().collect(),
+        Constant::Add(c) => vec![&c.operand0, &c.operand1],
+        Constant::Sub(c) => vec![&c.operand0, &c.operand1],
+        Constant::Mul(c) => vec![&c.operand0, &c.operand1],
+        #[cfg(feature = "llvm-14-or-lower")]
+        Constant::UDiv(c) => vec![&c.operand0, &c.operand1],
+        #[cfg(feature = "llvm-14-or-lower")]
+        Constant::SDiv(c) => vec![&c.operand0, &c.operand1],
+        #[cfg(feature = "llvm-14-or-lower")]
+        Constant::URem(c) => vec![&c.operand0, &c.operand1],
+        #[cfg(feature = "llvm-14-or-lower")]
+        Constant::SRem(c) => vec![&c.operand0, &c.operand1],
+        Constant::And(c) => vec![&c.operand0, &c.operand1],
+        Constant::Or(c) => vec![&c.operand0, &c.operand1],
+        Constant::Xor(c) => vec![&c.operand0, &c.operand1],
+        Constant::Shl(c) => vec![&c.operand0, &c.operand1],
+        Constant::LShr(c) => vec![&c.operand0, &c.operand1],
+        Constant::AShr(c) => vec![&c.operand0, &c.operand1],
+        #[cfg(feature = "llvm-14-or-lower")]
+        Constant::FAdd(c) => vec![&c.operand0, &c.operand1],
+        #[cfg(feature = "llvm-14-or-lower")]
+        Constant::FSub(c) => vec![&c.operand0, &c.operand1],
+        #[cfg(feature = "llvm-14-or-lower")]
+        Constant::FMul(c) => vec![&c.operand0, &c.operand1],
+        #[cfg(feature = "llvm-14-or-lower")]
+        Constant::FDiv(c) => vec![&c.operand0, &c.operand1],
+        #[cfg(feature = "llvm-14-or-lower")]
+        Constant::FRem(c) => vec![&c.operand0, &c.operand1],
+        Constant::ExtractElement(c) => vec![&c.vector, &c.index],
+        Constant::InsertElement(c) => vec![&c.vector, &c.element, &c.index],
+        Constant::ShuffleVector(c) => vec![&c.operand0, &c.operand1, &c.mask],
+        #[cfg(feature = "llvm-14-or-lower")]
+        Constant::ExtractValue(c) => vec![&c.aggregate],
+        #[cfg(feature = "llvm-14-or-lower")]
+        Constant::InsertValue(c) => vec![&c.aggregate, &c.element],
+        Constant::GetElementPtr(c) => std::iter::once(&c.address)
+            .chain(c.indices.iter())
+            .collect(),
+        Constant::Trunc(c) => vec![&c.operand],
+        Constant::ZExt(c) => vec![&c.operand],
+        Constant::SExt(c) => vec![&c.operand],
+        Constant::FPTrunc(c) => vec![&c.operand],
+        Constant::FPExt(c) => vec![&c.operand],
+        Constant::FPToUI(c) => vec![&c.operand],
+        Constant::FPToSI(c) => vec![&c.operand],
+        Constant::UIToFP(c) => vec![&c.operand],
+        Constant::SIToFP(c) => vec![&c.operand],
+        Constant::PtrToInt(c) => vec![&c.operand],
+        Constant::IntToPtr(c) => vec![&c.operand],
+        Constant::BitCast(c) => vec![&c.operand],
+        Constant::AddrSpaceCast(c) => vec![&c.operand],
+        Constant::ICmp(c) => vec![&c.operand0, &c.operand1],
+        Constant::FCmp(c) => vec![&c.operand0, &c.operand1],
+        Constant::Select(c) => vec![&c.condition, &c.true_value, &c.false_value],
+    }
+}
@@ -0,0 +1,315 @@
+use crate::control_dep_graph::ControlDependenceGraph;
+use crate::data_dep_graph::{
+    edges_with_kind, instructions, write_dependence_dot, DataDependenceGraph, DependenceKind,
+};
+use crate::def_use::InstructionLocation;
+use crate::dot::{dot_to_string, DotOptions};
+use llvm_ir::{Function, Instruction, Name};
+use petgraph::prelude::{DiGraphMap, Direction};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{self, Write};
+
+/// The program dependence graph for a particular function, combining the
+/// `DataDependenceGraph` with the `ControlDependenceGraph`, at the level of
+/// individual instructions (and terminators).
+///
+/// Each instruction in a block which has an immediate control dependence on
+/// another block (see
+/// [`ControlDependenceGraph`](struct.ControlDependenceGraph.html)) has a
+/// `DependenceKind::Control` dependence on the terminator of that block.
+/// In addition, each `phi` has a `DependenceKind::Control` dependence on the
+/// terminator of each of its predecessors, and on the terminators which those
+/// predecessors have an immediate control dependence on, since these decide
+/// which incoming value the `phi` takes.
+///
+/// To construct a `ProgramDependenceGraph`, use
+/// [`FunctionAnalysis`](struct.FunctionAnalysis.html), which you can get
+/// from [`ModuleAnalysis`](struct.ModuleAnalysis.html).
+pub struct ProgramDependenceGraph<'m> {
+    /// The graph itself. An edge from X to Y indicates that instruction X has
+    /// an immediate dependence on instruction Y. A path from X to Y indicates
+    /// that X has a dependence on Y. Every instruction and terminator in the
+    /// function is a node.
+    ///
+    /// Each edge is labeled with the `DependenceKind`(s) of the dependence.
+    graph: DiGraphMap<InstructionLocation<'m>, Vec<DependenceKind<'m>>>,
+
+    /// The function itself
+    function: &'m Function,
+}
+
+impl<'m> ProgramDependenceGraph<'m> {
+    pub(crate) fn new(ddg: &DataDependenceGraph<'m>, cdg: &ControlDependenceGraph<'m>) -> Self {
+        let mut graph = ddg.graph.clone();
+        let terminator = |block: &'m Name| InstructionLocation {
+            block,
+            index: ddg
+                .function
+                .get_bb_by_name(block)
+                .expect("control dependence should be on an existing block")
+                .instrs
+                .len(),
+        };
+        for bb in &ddg.function.basic_blocks {
+            for dep in cdg.get_imm_control_dependencies(&bb.name) {
+                let branch = terminator(dep);
+                for (index, _) in instructions(bb) {
+                    let location = InstructionLocation {
+                        block: &bb.name,
+                        index,
+                    };
+                    add_control_edge(&mut graph, location, branch);
+                }
+            }
+            // The value a phi selects depends on which predecessor we came
+            // from, so the phi depends on the predecessor's terminator, and on
+            // the branches which decide whether the predecessor executes
+            for (index, inst) in bb.instrs.iter().enumerate() {
+                if let Instruction::Phi(phi) = inst {
+                    let location = InstructionLocation {
+                        block: &bb.name,
+                        index,
+                    };
+                    for (_, pred) in &phi.incoming_values {
+                        add_control_edge(&mut graph, location, terminator(pred));
+                        for dep in cdg.get_imm_control_dependencies(pred) {
+                            add_control_edge(&mut graph, location, terminator(dep));
+                        }
+                    }
+                }
+            }
+        }
+
+        Self {
+            graph,
+            function: ddg.function,
+        }
+    }
+
+    /// Get the instructions which the instruction at the given location has an
+    /// immediate dependence on, along with the kind of each dependence.
+    ///
+    /// An instruction with several kinds of dependence on `location` appears
+    /// once for each kind.
+    pub fn get_imm_dependencies<'s>(
+        &'s self,
+        location: InstructionLocation<'m>,
+    ) -> impl Iterator<Item = (InstructionLocation<'m>, DependenceKind<'m>)> + 's {
+        edges_with_kind(&self.graph, location, Direction::Outgoing)
+    }
+
+    /// Get the instructions which have an immediate dependence on the
+    /// instruction at the given location, along with the kind of each
+    /// dependence. See notes on `get_imm_dependencies()`.
+    pub fn get_imm_dependents<'s>(
+        &'s self,
+        location: InstructionLocation<'m>,
+    ) -> impl Iterator<Item = (InstructionLocation<'m>, DependenceKind<'m>)> + 's {
+        edges_with_kind(&self.graph, location, Direction::Incoming)
+    }
+
+    /// Get the kinds of the immediate dependence of the instruction at `from`
+    /// on the instruction at `to`. This is empty if there is no such
+    /// dependence.
+    pub fn dependence_kinds(
+        &self,
+        from: InstructionLocation<'m>,
+        to: InstructionLocation<'m>,
+    ) -> &[DependenceKind<'m>] {
+        self.graph
+            .edge_weight(from, to)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Get a shortest chain of immediate dependences from the instruction at
+    /// `from` to the instruction at `to`, explaining why `from` depends on
+    /// `to`. The chain begins with `from` and ends with `to`; use
+    /// `dependence_kinds()` to get the kinds of each step.
+    ///
+    /// Returns `None` if `from` doesn't depend on `to`. If `from` and `to` are
+    /// the same, the chain is just that instruction.
+    pub fn dependence_chain(
+        &self,
+        from: InstructionLocation<'m>,
+        to: InstructionLocation<'m>,
+    ) -> Option<Vec<InstructionLocation<'m>>> {
+        if !self.graph.contains_node(from) {
+            return None;
+        }
+        // breadth-first search from `from`, recording how we reached each node
+        let mut reached_from: HashMap<InstructionLocation<'m>, InstructionLocation<'m>> =
+            HashMap::new();
+        let mut queue: VecDeque<InstructionLocation<'m>> = std::iter::once(from).collect();
+        while let Some(location) = queue.pop_front() {
+            if location == to {
+                let mut chain = vec![to];
+                let mut location = to;
+                while location != from {
+                    location = reached_from[&location];
+                    chain.push(location);
+                }
+                chain.reverse();
+                return Some(chain);
+            }
+            for dep in self.graph.neighbors_directed(location, Direction::Outgoing) {
+                if dep != from && !reached_from.contains_key(&dep) {
+                    reached_from.insert(dep, location);
+                    queue.push_back(dep);
+                }
+            }
+        }
+        None
+    }
+
+    /// Get the backward slice from the instruction at `criterion`: the
+    /// instructions which it depends on, directly or transitively, and so may
+    /// affect whether it executes or the values it uses. The slice includes
+    /// `criterion` itself.
+    ///
+    /// If there is no instruction at `criterion`, the slice is empty.
+    pub fn backward_slice(&self, criterion: InstructionLocation<'m>) -> Slice<'m> {
+        self.slice(criterion, Direction::Outgoing)
+    }
+
+    /// Get the forward slice from the instruction at `criterion`: the
+    /// instructions which depend on it, directly or transitively, and so may be
+    /// affected by whether it executes or the value it produces. The slice
+    /// includes `criterion` itself.
+    ///
+    /// If there is no instruction at `criterion`, the slice is empty.
+    pub fn forward_slice(&self, criterion: InstructionLocation<'m>) -> Slice<'m> {
+        self.slice(criterion, Direction::Incoming)
+    }
+
+    /// Get the chop from the instruction at `source` to the instruction at
+    /// `sink`: the instructions through which `source` may affect `sink`.
+    /// This is the intersection of the forward slice from `source` and the
+    /// backward slice from `sink`.
+    ///
+    /// The chop is empty if `sink` doesn't depend on `source`.
+    pub fn chop(
+        &self,
+        source: InstructionLocation<'m>,
+        sink: InstructionLocation<'m>,
+    ) -> Slice<'m> {
+        let forward = self.forward_slice(source);
+        let backward = self.backward_slice(sink);
+        Slice {
+            instructions: forward
+                .instructions
+                .intersection(&backward.instructions)
+                .copied()
+                .collect(),
+            function: self.function,
+        }
+    }
+
+    /// Get the slice of all the nodes reachable from `criterion` following
+    /// edges in the given `direction`
+    fn slice(&self, criterion: InstructionLocation<'m>, direction: Direction) -> Slice<'m> {
+        let mut instructions = HashSet::new();
+        if self.graph.contains_node(criterion) {
+            let mut worklist = vec![criterion];
+            while let Some(location) = worklist.pop() {
+                if instructions.insert(location) {
+                    worklist.extend(self.graph.neighbors_directed(location, direction));
+                }
+            }
+        }
+        Slice {
+            instructions,
+            function: self.function,
+        }
+    }
+
+    /// Render the program dependence graph in the Graphviz DOT format, with
+    /// the given options.
+    ///
+    /// An edge from X to Y indicates that instruction X has an immediate
+    /// dependence on instruction Y.
+    pub fn to_dot(&self, options: &DotOptions<'m>) -> String {
+        dot_to_string(|w| self.write_dot(w, options))
+    }
+
+    /// Write the program dependence graph in the Graphviz DOT format to `w`,
+    /// with the given options
+    pub fn write_dot(&self, w: &mut impl Write, options: &DotOptions<'m>) -> io::Result<()> {
+        write_dependence_dot(w, &self.graph, self.function, options)
+    }
+}
+
+/// Add a `DependenceKind::Control` dependence of the instruction at `from` on
+/// the terminator at `to`, unless there already is one
+fn add_control_edge<'m>(
+    graph: &mut DiGraphMap<InstructionLocation<'m>, Vec<DependenceKind<'m>>>,
+    from: InstructionLocation<'m>,
+    to: InstructionLocation<'m>,
+) {
+    match graph.edge_weight_mut(from, to) {
+        Some(kinds) => {
+            if !kinds.contains(&DependenceKind::Control) {
+                kinds.push(DependenceKind::Control);
+            }
+        }
+        None => {
+            graph.add_edge(from, to, vec![DependenceKind::Control]);
+        }
+    }
+}
+
+/// A set of instructions (and terminators) in a particular function, computed
+/// by slicing or chopping the `ProgramDependenceGraph`. See
+/// [`ProgramDependenceGraph::backward_slice()`](struct.ProgramDependenceGraph.html#method.backward_slice).
+pub struct Slice<'m> {
+    /// The instructions in the slice
+    instructions: HashSet<InstructionLocation<'m>>,
+
+    /// The function itself
+    function: &'m Function,
+}
+
+impl<'m> Slice<'m> {
+    /// Is the instruction at the given location in the slice?
+    pub fn contains(&self, location: InstructionLocation<'m>) -> bool {
+        self.instructions.contains(&location)
+    }
+
+    /// Iterate over the instructions in the slice, in the order they appear in
+    /// the function
+    pub fn instructions<'s>(&'s self) -> impl Iterator<Item = InstructionLocation<'m>> + 's {
+        self.function.basic_blocks.iter().flat_map(move |bb| {
+            (0..=bb.instrs.len())
+                .map(move |index| InstructionLocation {
+                    block: &bb.name,
+                    index,
+                })
+                .filter(move |location| self.instructions.contains(location))
+        })
+    }
+
+    /// Iterate over the basic blocks containing at least one instruction in
+    /// the slice, in the order they appear in the function
+    pub fn blocks<'s>(&'s self) -> impl Iterator<Item = &'m Name> + 's {
+        let blocks: HashSet<&'m Name> = self
+            .instructions
+            .iter()
+            .map(|location| location.block)
+            .collect();
+        self.function
+            .basic_blocks
+            .iter()
+            .map(|bb| &bb.name)
+            .filter(move |name| blocks.contains(name))
+    }
+
+    /// Get the number of instructions in the slice
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    /// Is the slice empty?
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+}
@@ -0,0 +1,217 @@
+// asmgoto.bc was generated by clang 14, so only LLVM 14 or later can read it
+#![cfg(feature = "llvm-14-or-greater")]
+
+use itertools::Itertools;
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// asmgoto.c uses `asm goto`, which compiles to the `callbr` terminator.
+///
+/// Note that `llvm-ir` gives the (void) result of each `callbr` a number, so
+/// the block numbers used here are one higher than the ones in asmgoto.ll.
+const ASMGOTO_BC_PATH: &str = "tests/bcfiles/asmgoto.bc";
+
+#[test]
+fn asm_goto_simple_cfg() {
+    init_logging();
+    let module = Module::from_bc_path(ASMGOTO_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let cfg = analysis.fn_analysis("asm_goto_simple").control_flow_graph();
+
+    // CFG:
+    //   1
+    //   | \
+    //   3  |  (1 -> 4 is the asm goto)
+    //   | /
+    //   4
+    //   |
+    //  ret
+
+    let bb1_name = Name::from(1);
+    let bb3_name = Name::from(3);
+    let bb3_node = CFGNode::Block(&bb3_name);
+    let bb4_name = Name::from(4);
+    let bb4_node = CFGNode::Block(&bb4_name);
+
+    let bb1_preds: Vec<&Name> = cfg.preds(&bb1_name).sorted().collect();
+    assert!(bb1_preds.is_empty());
+    let bb1_succs: Vec<CFGNode> = cfg.succs(&bb1_name).sorted().collect();
+    assert_eq!(bb1_succs, vec![bb3_node, bb4_node]);
+
+    let bb3_preds: Vec<&Name> = cfg.preds(&bb3_name).sorted().collect();
+    assert_eq!(bb3_preds, vec![&bb1_name]);
+    let bb3_succs: Vec<CFGNode> = cfg.succs(&bb3_name).sorted().collect();
+    assert_eq!(bb3_succs, vec![bb4_node]);
+
+    let bb4_preds: Vec<&Name> = cfg.preds(&bb4_name).sorted().collect();
+    assert_eq!(bb4_preds, vec![&bb1_name, &bb3_name]);
+    let bb4_succs: Vec<CFGNode> = cfg.succs(&bb4_name).sorted().collect();
+    assert_eq!(bb4_succs, vec![CFGNode::Return]);
+}
+
+#[test]
+fn asm_goto_two_labels_cfg() {
+    init_logging();
+    let module = Module::from_bc_path(ASMGOTO_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let cfg = analysis
+        .fn_analysis("asm_goto_two_labels")
+        .control_flow_graph();
+
+    // CFG:
+    //      1
+    //    / | \
+    //   3  5  6  (1 -> 5 and 1 -> 6 are the asm goto)
+    //    \ | /
+    //      7
+    //      |
+    //     ret
+    //
+    // Since `llvm-ir` can't tell us the destinations of the `callbr`, we
+    // also conservatively get an edge 1 -> 7
+
+    let bb1_name = Name::from(1);
+    let bb3_name = Name::from(3);
+    let bb3_node = CFGNode::Block(&bb3_name);
+    let bb5_name = Name::from(5);
+    let bb5_node = CFGNode::Block(&bb5_name);
+    let bb6_name = Name::from(6);
+    let bb6_node = CFGNode::Block(&bb6_name);
+    let bb7_name = Name::from(7);
+    let bb7_node = CFGNode::Block(&bb7_name);
+
+    let bb1_succs: Vec<CFGNode> = cfg.succs(&bb1_name).sorted().collect();
+    assert_eq!(bb1_succs, vec![bb3_node, bb5_node, bb6_node, bb7_node]);
+
+    for bb_name in &[&bb3_name, &bb5_name, &bb6_name] {
+        let preds: Vec<&Name> = cfg.preds(bb_name).sorted().collect();
+        assert_eq!(preds, vec![&bb1_name]);
+        let succs: Vec<CFGNode> = cfg.succs(bb_name).sorted().collect();
+        assert_eq!(succs, vec![bb7_node]);
+    }
+
+    let bb7_preds: Vec<&Name> = cfg.preds(&bb7_name).sorted().collect();
+    assert_eq!(bb7_preds, vec![&bb1_name, &bb3_name, &bb5_name, &bb6_name]);
+    let bb7_succs: Vec<CFGNode> = cfg.succs(&bb7_name).sorted().collect();
+    assert_eq!(bb7_succs, vec![CFGNode::Return]);
+}
+
+#[test]
+fn asm_goto_two_labels_domtree() {
+    init_logging();
+    let module = Module::from_bc_path(ASMGOTO_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("asm_goto_two_labels");
+    let domtree = fn_analysis.dominator_tree();
+    let postdomtree = fn_analysis.postdominator_tree();
+
+    let bb1_name = Name::from(1);
+    let bb3_name = Name::from(3);
+    let bb5_name = Name::from(5);
+    let bb6_name = Name::from(6);
+    let bb7_name = Name::from(7);
+    let bb7_node = CFGNode::Block(&bb7_name);
+
+    assert_eq!(domtree.idom(&bb1_name), None);
+    assert_eq!(domtree.idom(&bb3_name), Some(&bb1_name));
+    assert_eq!(domtree.idom(&bb5_name), Some(&bb1_name));
+    assert_eq!(domtree.idom(&bb6_name), Some(&bb1_name));
+    assert_eq!(domtree.idom(&bb7_name), Some(&bb1_name));
+    assert_eq!(domtree.idom_of_return(), Some(&bb7_name));
+
+    assert_eq!(postdomtree.ipostdom(&bb1_name), Some(bb7_node));
+    assert_eq!(postdomtree.ipostdom(&bb3_name), Some(bb7_node));
+    assert_eq!(postdomtree.ipostdom(&bb5_name), Some(bb7_node));
+    assert_eq!(postdomtree.ipostdom(&bb6_name), Some(bb7_node));
+    assert_eq!(postdomtree.ipostdom(&bb7_name), Some(CFGNode::Return));
+}
+
+#[test]
+fn asm_goto_call_graph() {
+    init_logging();
+    let module = Module::from_bc_path(ASMGOTO_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let callers: Vec<&str> = callgraph
+        .callers("asm_goto_callee")
+        .unwrap()
+        .sorted()
+        .collect();
+    assert_eq!(callers, vec!["asm_goto_two_labels"]);
+
+    // the `callbr`s themselves call inline assembly, which doesn't appear in
+    // the call graph
+    let callees: Vec<&str> = callgraph
+        .callees("asm_goto_simple")
+        .unwrap()
+        .sorted()
+        .collect();
+    assert!(callees.is_empty());
+    let callees: Vec<&str> = callgraph
+        .callees("asm_goto_two_labels")
+        .unwrap()
+        .sorted()
+        .collect();
+    assert_eq!(callees, vec!["asm_goto_callee"]);
+}
+
+#[test]
+fn asm_goto_simple_slicing() {
+    init_logging();
+    let module = Module::from_bc_path(ASMGOTO_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let pdg = analysis
+        .fn_analysis("asm_goto_simple")
+        .program_dependence_graph();
+
+    let bb1_name = Name::from(1);
+    let bb3_name = Name::from(3);
+    let bb4_name = Name::from(4);
+    let callbr = InstructionLocation {
+        block: &bb1_name,
+        index: 0,
+    };
+    let br = InstructionLocation {
+        block: &bb3_name,
+        index: 0,
+    };
+    let phi = InstructionLocation {
+        block: &bb4_name,
+        index: 0,
+    };
+    let ret = InstructionLocation {
+        block: &bb4_name,
+        index: 1,
+    };
+
+    // no block is control-dependent on the `callbr`, but the value of the phi
+    // depends on which way it went
+    let deps: Vec<(InstructionLocation, DependenceKind)> =
+        pdg.get_imm_dependencies(phi).sorted().collect();
+    assert_eq!(
+        deps,
+        vec![
+            (callbr, DependenceKind::Control),
+            (br, DependenceKind::Control)
+        ]
+    );
+
+    let slice = pdg.backward_slice(ret);
+    let instructions: Vec<InstructionLocation> = slice.instructions().collect();
+    assert_eq!(instructions, vec![callbr, br, phi, ret]);
+    assert_eq!(
+        pdg.dependence_chain(ret, callbr),
+        Some(vec![ret, phi, callbr])
+    );
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::bool_assert_comparison)]
+
 use itertools::Itertools;
 use llvm_ir::{Module, Name};
 use llvm_ir_analysis::*;
@@ -10,10 +12,10 @@ fn init_logging() {
 /// basic.c and basic.bc are taken from [`haybale`]'s test suite
 ///
 /// [`haybale`]: https://crates.io/crates/haybale
-const BASIC_BC_PATH: &'static str = "tests/bcfiles/basic.bc";
+const BASIC_BC_PATH: &str = "tests/bcfiles/basic.bc";
 
 /// Function names in basic.bc
-const FUNC_NAMES: &'static [&'static str] = &[
+const FUNC_NAMES: &[&str] = &[
     "no_args_zero",
     "no_args_nozero",
     "one_arg",
@@ -450,6 +452,105 @@ fn has_switch_cfg() {
     assert_eq!(bb14_succs, vec![CFGNode::Return]);
 }
 
+#[test]
+fn conditional_true_cfg_edge_kinds() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let cfg = analysis
+        .fn_analysis("conditional_true")
+        .control_flow_graph();
+
+    let bb2_name = Name::from(2);
+    let bb4_name = Name::from(4);
+    let bb4_node = CFGNode::Block(&bb4_name);
+    let bb8_name = Name::from(8);
+    let bb8_node = CFGNode::Block(&bb8_name);
+    let bb12_name = Name::from(12);
+    let bb12_node = CFGNode::Block(&bb12_name);
+
+    let bb2_succs: Vec<(CFGNode, CFGEdgeKind)> = cfg
+        .succs_with_kind(&bb2_name)
+        .sorted_by_key(|(n, _)| *n)
+        .collect();
+    assert_eq!(
+        bb2_succs,
+        vec![
+            (bb4_node, CFGEdgeKind::BranchTrue),
+            (bb8_node, CFGEdgeKind::BranchFalse)
+        ]
+    );
+
+    let bb4_preds: Vec<(&Name, CFGEdgeKind)> = cfg.preds_with_kind(&bb4_name).collect();
+    assert_eq!(bb4_preds, vec![(&bb2_name, CFGEdgeKind::BranchTrue)]);
+    let bb4_succs: Vec<(CFGNode, CFGEdgeKind)> = cfg.succs_with_kind(&bb4_name).collect();
+    assert_eq!(bb4_succs, vec![(bb12_node, CFGEdgeKind::Branch)]);
+
+    let bb8_preds: Vec<(&Name, CFGEdgeKind)> = cfg.preds_with_kind(&bb8_name).collect();
+    assert_eq!(bb8_preds, vec![(&bb2_name, CFGEdgeKind::BranchFalse)]);
+
+    let bb12_succs: Vec<(CFGNode, CFGEdgeKind)> = cfg.succs_with_kind(&bb12_name).collect();
+    assert_eq!(bb12_succs, vec![(CFGNode::Return, CFGEdgeKind::Return)]);
+    let return_preds: Vec<(&Name, CFGEdgeKind)> = cfg.preds_of_return_with_kind().collect();
+    assert_eq!(return_preds, vec![(&bb12_name, CFGEdgeKind::Return)]);
+}
+
+#[test]
+fn has_switch_cfg_edge_kinds() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let cfg = analysis.fn_analysis("has_switch").control_flow_graph();
+
+    let bb2_name = Name::from(2);
+    let bb4_name = Name::from(4);
+    let bb12_name = Name::from(12);
+    let bb14_name = Name::from(14);
+
+    let bb2_succs: Vec<(String, String)> = cfg
+        .succs_with_kind(&bb2_name)
+        .map(|(node, kind)| (node.to_string(), kind.to_string()))
+        .sorted()
+        .collect();
+    assert_eq!(
+        bb2_succs,
+        vec![
+            ("%10".to_owned(), "case i32 33".to_owned()),
+            ("%11".to_owned(), "case i32 451".to_owned()),
+            ("%12".to_owned(), "default".to_owned()),
+            ("%14".to_owned(), "case i32 0".to_owned()),
+            ("%4".to_owned(), "case i32 1".to_owned()),
+            ("%5".to_owned(), "case i32 2".to_owned()),
+            ("%7".to_owned(), "case i32 3".to_owned()),
+        ]
+    );
+
+    let bb12_preds: Vec<(&Name, CFGEdgeKind)> = cfg.preds_with_kind(&bb12_name).collect();
+    assert_eq!(bb12_preds, vec![(&bb2_name, CFGEdgeKind::SwitchDefault)]);
+
+    let bb14_preds: Vec<(&Name, CFGEdgeKind)> = cfg.preds_with_kind(&bb14_name).collect();
+    assert_eq!(bb14_preds.len(), 7);
+    for (pred, kind) in bb14_preds {
+        if pred == &bb2_name {
+            assert!(matches!(kind, CFGEdgeKind::SwitchCase(_)));
+        } else {
+            assert_eq!(kind, CFGEdgeKind::Branch);
+        }
+    }
+
+    let bb4_preds: Vec<(&Name, CFGEdgeKind)> = cfg.preds_with_kind(&bb4_name).collect();
+    assert_eq!(bb4_preds.len(), 1);
+    match bb4_preds[0] {
+        (pred, CFGEdgeKind::SwitchCase(val)) => {
+            assert_eq!(pred, &bb2_name);
+            assert_eq!(val.to_string(), "i32 1");
+        }
+        (_, kind) => panic!("Expected a switch case, got {:?}", kind),
+    }
+}
+
 #[test]
 fn trivial_domtrees() {
     init_logging();
@@ -548,6 +649,37 @@ fn trivial_domtrees() {
     }
 }
 
+#[test]
+fn trivial_nearest_common_dominators() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("no_args_zero");
+    let entry = CFGNode::Block(fn_analysis.dominator_tree().entry());
+
+    let domtree = fn_analysis.dominator_tree();
+    assert_eq!(domtree.nearest_common_dominator(entry, entry), Some(entry));
+    assert_eq!(
+        domtree.nearest_common_dominator(entry, CFGNode::Return),
+        Some(entry)
+    );
+    assert_eq!(
+        domtree.nearest_common_dominator(CFGNode::Return, CFGNode::Return),
+        Some(CFGNode::Return)
+    );
+
+    let postdomtree = fn_analysis.postdominator_tree();
+    assert_eq!(
+        postdomtree.nearest_common_postdominator(entry, entry),
+        Some(entry)
+    );
+    assert_eq!(
+        postdomtree.nearest_common_postdominator(entry, CFGNode::Return),
+        Some(CFGNode::Return)
+    );
+}
+
 #[test]
 fn conditional_true_domtree() {
     init_logging();
@@ -1264,3 +1396,195 @@ fn has_switch_cdg() {
     assert_eq!(cdg.is_control_dependent(&bb14_name, &bb2_name), false);
     assert_eq!(cdg.is_control_dependent(&bb4_name, &bb12_name), false);
 }
+
+#[test]
+fn conditional_true_dot() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("conditional_true");
+
+    let cfg = fn_analysis.control_flow_graph();
+    let dot = cfg.to_dot(&DotOptions::default());
+    assert!(dot.starts_with("digraph \"conditional_true\" {\n"));
+    assert!(dot.contains("  \"%4\" [label=\"%4\"];\n"));
+    assert!(dot.contains("  \"%2\" -> \"%4\";\n"));
+    assert!(dot.contains("  \"%12\" -> \"Return\";\n"));
+    assert!(dot.ends_with("}\n"));
+
+    let bb4_name = Name::from(4);
+    let options = DotOptions {
+        instructions: true,
+        edge_kinds: true,
+        highlight: std::iter::once(CFGNode::Block(&bb4_name)).collect(),
+        ..Default::default()
+    };
+    let dot = cfg.to_dot(&options);
+    assert!(dot.contains(
+        "  \"%4\" [label=\"%4:\\l  %5 = add i32 %0, i32 -1\\l  %6 = add i32 %1, i32 -1\\l  %7 = mul i32 %6, i32 %5\\l  br label %12\\l\", style=filled, fillcolor=yellow];\n"
+    ));
+    assert!(dot.contains("  \"%2\" -> \"%4\" [label=\"true\"];\n"));
+    assert!(dot.contains("  \"%2\" -> \"%8\" [label=\"false\"];\n"));
+    assert!(dot.contains("  \"%12\" -> \"Return\" [label=\"ret\"];\n"));
+
+    let mut bytes = vec![];
+    fn_analysis
+        .postdominator_tree()
+        .write_dot(&mut bytes, &DotOptions::default())
+        .unwrap();
+    let dot = String::from_utf8(bytes).unwrap();
+    let edges: Vec<&str> = dot.lines().filter(|l| l.contains("->")).sorted().collect();
+    assert_eq!(
+        edges,
+        vec![
+            "  \"%12\" -> \"%2\";",
+            "  \"%12\" -> \"%4\";",
+            "  \"%12\" -> \"%8\";",
+            "  \"Return\" -> \"%12\";",
+        ]
+    );
+}
+
+#[test]
+fn analyses_are_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ModuleAnalysis>();
+    assert_send_sync::<CrossModuleAnalysis>();
+    assert_send_sync::<FunctionAnalysis>();
+
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    // compute analyses for all the functions from several threads at once,
+    // with the threads racing to fill the same caches
+    let results: Vec<Vec<(String, String)>> = std::thread::scope(|s| {
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                s.spawn(|| {
+                    FUNC_NAMES
+                        .iter()
+                        .map(|func_name| {
+                            let fn_analysis = analysis.fn_analysis(func_name);
+                            let options = DotOptions::default();
+                            let domtree = fn_analysis.dominator_tree().to_dot(&options);
+                            let cdg = fn_analysis.control_dependence_graph().to_dot(&options);
+                            (domtree, cdg)
+                        })
+                        .collect()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert!(results.iter().all_equal());
+
+    // the threads all saw the same cached analyses
+    let domtree: *const DominatorTree = analysis.fn_analysis("conditional_true").dominator_tree();
+    let domtree_again: *const DominatorTree =
+        analysis.fn_analysis("conditional_true").dominator_tree();
+    assert_eq!(domtree, domtree_again);
+}
+
+#[test]
+fn precompute() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let precomputed = ModuleAnalysis::new(&module);
+    precomputed.precompute(AnalysisKind::ALL);
+    let lazy = ModuleAnalysis::new(&module);
+    for func_name in FUNC_NAMES {
+        for &kind in AnalysisKind::ALL {
+            assert!(precomputed.fn_analysis(func_name).is_computed(kind));
+            assert!(!lazy.fn_analysis(func_name).is_computed(kind));
+        }
+    }
+
+    // precomputed analyses are the same as those computed on demand. The
+    // order of lines in DOT output is unspecified, so we compare them sorted
+    let options = DotOptions::default();
+    let lines = |dot: String| -> Vec<String> { dot.lines().map(str::to_owned).sorted().collect() };
+    for func_name in FUNC_NAMES {
+        let precomputed = precomputed.fn_analysis(func_name);
+        let lazy = lazy.fn_analysis(func_name);
+        assert_eq!(
+            lines(precomputed.control_flow_graph().to_dot(&options)),
+            lines(lazy.control_flow_graph().to_dot(&options))
+        );
+        assert_eq!(
+            lines(precomputed.dominator_tree().to_dot(&options)),
+            lines(lazy.dominator_tree().to_dot(&options))
+        );
+        assert_eq!(
+            lines(precomputed.postdominator_tree().to_dot(&options)),
+            lines(lazy.postdominator_tree().to_dot(&options))
+        );
+        assert_eq!(
+            lines(precomputed.control_dependence_graph().to_dot(&options)),
+            lines(lazy.control_dependence_graph().to_dot(&options))
+        );
+    }
+
+    let cross_mod = CrossModuleAnalysis::new(std::iter::once(&module));
+    cross_mod.precompute_with(
+        &[AnalysisKind::DominatorTree],
+        ExceptionalFlow::SeparateUnwind,
+    );
+    let mod_analysis = cross_mod.module_analysis(&module.name);
+    for func_name in FUNC_NAMES {
+        let fn_analysis = mod_analysis.fn_analysis(func_name);
+        assert!(fn_analysis
+            .is_computed_with(AnalysisKind::DominatorTree, ExceptionalFlow::SeparateUnwind));
+        // the dominator tree is computed from the control flow graph
+        assert!(fn_analysis.is_computed_with(
+            AnalysisKind::ControlFlowGraph,
+            ExceptionalFlow::SeparateUnwind
+        ));
+        // other analyses, and the dominator tree with other `ExceptionalFlow`s,
+        // are still computed lazily
+        assert!(!fn_analysis.is_computed_with(
+            AnalysisKind::PostDominatorTree,
+            ExceptionalFlow::SeparateUnwind
+        ));
+        assert!(!fn_analysis.is_computed(AnalysisKind::DominatorTree));
+    }
+}
+
+#[test]
+fn conditional_true_fallible_lookups() {
+    init_logging();
+    let module = Module::from_bc_path(BASIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    assert!(analysis.try_fn_analysis("nonexistent").is_err());
+    let fn_analysis = analysis
+        .try_fn_analysis("conditional_true")
+        .expect("function should be found");
+
+    let bb2_name = Name::from(2);
+    let bb4_name = Name::from(4);
+    let bb12_name = Name::from(12);
+
+    let cfg = fn_analysis.control_flow_graph();
+    assert_eq!(cfg.try_entry().unwrap(), &bb2_name);
+
+    let domtree = fn_analysis.dominator_tree();
+    assert_eq!(domtree.try_entry().unwrap(), &bb2_name);
+    assert_eq!(domtree.try_idom(&bb2_name).unwrap(), None);
+    assert_eq!(domtree.try_idom(&bb4_name).unwrap(), Some(&bb2_name));
+    assert_eq!(domtree.try_idom_of_return().unwrap(), Some(&bb12_name));
+    assert_eq!(domtree.try_idom_of_unwind().unwrap(), None);
+
+    let postdomtree = fn_analysis.postdominator_tree();
+    assert_eq!(
+        postdomtree.try_ipostdom(&bb4_name).unwrap(),
+        Some(CFGNode::Block(&bb12_name))
+    );
+
+    let cdg = fn_analysis.control_dependence_graph();
+    assert_eq!(cdg.try_entry().unwrap(), &bb2_name);
+}
@@ -0,0 +1,23 @@
+typedef int (*inttype)(int);
+
+__attribute__((noinline)) int taken(int x) {
+  return x + 1;
+}
+
+__attribute__((noinline)) int in_table(int x) {
+  return x * 2;
+}
+
+__attribute__((noinline)) int not_taken(int x) {
+  return x - 1;
+}
+
+inttype table[] = { &in_table };
+
+__attribute__((noinline)) int calls_fptr(volatile inttype fptr, int z) {
+  return fptr(z);
+}
+
+int addrtaken_driver(int z) {
+  return calls_fptr(&taken, z) + not_taken(z);
+}
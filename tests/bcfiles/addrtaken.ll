@@ -0,0 +1,44 @@
+; ModuleID = 'addrtaken.c'
+source_filename = "addrtaken.c"
+target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-linux-gnu"
+
+@table = dso_local global [1 x i32 (i32)*] [i32 (i32)* @in_table], align 8
+
+; Function Attrs: noinline nounwind uwtable
+define dso_local i32 @taken(i32 %0) #0 {
+  %2 = add nsw i32 %0, 1
+  ret i32 %2
+}
+
+; Function Attrs: noinline nounwind uwtable
+define dso_local i32 @in_table(i32 %0) #0 {
+  %2 = shl nsw i32 %0, 1
+  ret i32 %2
+}
+
+; Function Attrs: noinline nounwind uwtable
+define dso_local i32 @not_taken(i32 %0) #0 {
+  %2 = add nsw i32 %0, -1
+  ret i32 %2
+}
+
+; Function Attrs: noinline nounwind uwtable
+define dso_local i32 @calls_fptr(i32 (i32)* %0, i32 %1) #0 {
+  %3 = alloca i32 (i32)*, align 8
+  store volatile i32 (i32)* %0, i32 (i32)** %3, align 8
+  %4 = load volatile i32 (i32)*, i32 (i32)** %3, align 8
+  %5 = call i32 %4(i32 %1)
+  ret i32 %5
+}
+
+; Function Attrs: nounwind uwtable
+define dso_local i32 @addrtaken_driver(i32 %0) #1 {
+  %2 = call i32 @calls_fptr(i32 (i32)* @taken, i32 %0)
+  %3 = call i32 @not_taken(i32 %0)
+  %4 = add nsw i32 %2, %3
+  ret i32 %4
+}
+
+attributes #0 = { noinline nounwind uwtable }
+attributes #1 = { nounwind uwtable }
@@ -0,0 +1,23 @@
+// Functions using `asm goto`, which clang compiles to the `callbr`
+// terminator. The assembly is x86, but none of it is ever executed; we only
+// analyze the resulting IR.
+
+int asm_goto_callee(int x) {
+    return x + 3;
+}
+
+int asm_goto_simple(int x) {
+    asm goto("testl %0, %0; jne %l1" : : "r"(x) : : error);
+    return 0;
+error:
+    return -1;
+}
+
+int asm_goto_two_labels(int x) {
+    asm goto("cmpl $1, %0; je %l1; cmpl $2, %0; je %l2" : : "r"(x) : : one, two);
+    return asm_goto_callee(x);
+one:
+    return 1;
+two:
+    return 2;
+}
@@ -0,0 +1,58 @@
+; ModuleID = 'asmgoto.c'
+source_filename = "asmgoto.c"
+target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-linux-gnu"
+
+; Function Attrs: noinline nounwind readnone uwtable
+define dso_local i32 @asm_goto_callee(i32 noundef %0) local_unnamed_addr #0 {
+  %2 = add nsw i32 %0, 3
+  ret i32 %2
+}
+
+; Function Attrs: nounwind uwtable
+define dso_local i32 @asm_goto_simple(i32 noundef %0) local_unnamed_addr #1 {
+  callbr void asm sideeffect "testl $0, $0; jne ${1:l}", "r,X,~{dirflag},~{fpsr},~{flags}"(i32 %0, i8* blockaddress(@asm_goto_simple, %3)) #2
+          to label %2 [label %3], !srcloc !5
+
+2:                                                ; preds = %1
+  br label %3
+
+3:                                                ; preds = %1, %2
+  %4 = phi i32 [ 0, %2 ], [ -1, %1 ]
+  ret i32 %4
+}
+
+; Function Attrs: nounwind uwtable
+define dso_local i32 @asm_goto_two_labels(i32 noundef %0) local_unnamed_addr #1 {
+  callbr void asm sideeffect "cmpl $$1, $0; je ${1:l}; cmpl $$2, $0; je ${2:l}", "r,X,X,~{dirflag},~{fpsr},~{flags}"(i32 %0, i8* blockaddress(@asm_goto_two_labels, %4), i8* blockaddress(@asm_goto_two_labels, %5)) #2
+          to label %2 [label %4, label %5], !srcloc !6
+
+2:                                                ; preds = %1
+  %3 = tail call i32 @asm_goto_callee(i32 noundef %0)
+  br label %6
+
+4:                                                ; preds = %1
+  br label %6
+
+5:                                                ; preds = %1
+  br label %6
+
+6:                                                ; preds = %2, %4, %5
+  %7 = phi i32 [ %3, %2 ], [ 1, %4 ], [ 2, %5 ]
+  ret i32 %7
+}
+
+attributes #0 = { noinline nounwind readnone uwtable "frame-pointer"="none" "min-legal-vector-width"="0" "no-trapping-math"="true" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "tune-cpu"="generic" }
+attributes #1 = { nounwind uwtable "frame-pointer"="none" "min-legal-vector-width"="0" "no-trapping-math"="true" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "tune-cpu"="generic" }
+attributes #2 = { nounwind }
+
+!llvm.module.flags = !{!0, !1, !2, !3}
+!llvm.ident = !{!4}
+
+!0 = !{i32 1, !"wchar_size", i32 4}
+!1 = !{i32 7, !"PIC Level", i32 2}
+!2 = !{i32 7, !"PIE Level", i32 2}
+!3 = !{i32 7, !"uwtable", i32 1}
+!4 = !{!"clang version 14.0.6"}
+!5 = !{i64 141}
+!6 = !{i64 318}
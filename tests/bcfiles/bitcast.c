@@ -0,0 +1,12 @@
+// A call through an unprototyped declaration, which clang compiles (at -O0)
+// to a call of the callee bitcast to a different function type
+
+int bitcast_callee();
+
+int bitcast_caller(int x) {
+    return bitcast_callee(x, 2);
+}
+
+int bitcast_callee(int a, int b) {
+    return a + b;
+}
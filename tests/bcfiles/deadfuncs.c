@@ -0,0 +1,33 @@
+static int used_static(int x) {
+  return x + 1;
+}
+
+static int taken_static(int x) {
+  return x * 3;
+}
+
+int (*fptr)(int) = &taken_static;
+
+static int dead_callee(int x) {
+  return x - 7;
+}
+
+static int dead_static(int x) {
+  return dead_callee(x) * 2;
+}
+
+static int dead_recursive(int x) {
+  return x <= 0 ? 0 : dead_recursive(x - 1) + 1;
+}
+
+static int only_from_exported(int x) {
+  return x ^ 5;
+}
+
+int exported(int x) {
+  return only_from_exported(x);
+}
+
+int main(void) {
+  return used_static(2);
+}
@@ -0,0 +1,66 @@
+; ModuleID = 'deadfuncs.c'
+source_filename = "deadfuncs.c"
+target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-linux-gnu"
+
+@fptr = dso_local global i32 (i32)* @taken_static, align 8
+
+; Function Attrs: noinline nounwind uwtable
+define internal i32 @used_static(i32 %0) #0 {
+  %2 = add nsw i32 %0, 1
+  ret i32 %2
+}
+
+; Function Attrs: noinline nounwind uwtable
+define internal i32 @taken_static(i32 %0) #0 {
+  %2 = mul nsw i32 %0, 3
+  ret i32 %2
+}
+
+; Function Attrs: noinline nounwind uwtable
+define internal i32 @dead_callee(i32 %0) #0 {
+  %2 = add nsw i32 %0, -7
+  ret i32 %2
+}
+
+; Function Attrs: noinline nounwind uwtable
+define internal i32 @dead_static(i32 %0) #0 {
+  %2 = call i32 @dead_callee(i32 %0)
+  %3 = shl nsw i32 %2, 1
+  ret i32 %3
+}
+
+; Function Attrs: noinline nounwind uwtable
+define private i32 @dead_recursive(i32 %0) #0 {
+  %2 = icmp slt i32 %0, 1
+  br i1 %2, label %6, label %3
+
+3:                                                ; preds = %1
+  %4 = add nsw i32 %0, -1
+  %5 = call i32 @dead_recursive(i32 %4)
+  br label %6
+
+6:                                                ; preds = %1, %3
+  %7 = phi i32 [ 0, %1 ], [ %5, %3 ]
+  ret i32 %7
+}
+
+; Function Attrs: noinline nounwind uwtable
+define internal i32 @only_from_exported(i32 %0) #0 {
+  %2 = xor i32 %0, 5
+  ret i32 %2
+}
+
+; Function Attrs: noinline nounwind uwtable
+define dso_local i32 @exported(i32 %0) #0 {
+  %2 = call i32 @only_from_exported(i32 %0)
+  ret i32 %2
+}
+
+; Function Attrs: noinline nounwind uwtable
+define dso_local i32 @main() #0 {
+  %1 = call i32 @used_static(i32 2)
+  ret i32 %1
+}
+
+attributes #0 = { noinline nounwind uwtable }
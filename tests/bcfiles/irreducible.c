@@ -0,0 +1,24 @@
+// Functions with irreducible control flow, i.e., cycles which can be entered
+// at more than one block
+
+int irreducible_goto(int x) {
+    if (x) goto b;
+a:
+    x -= 1;
+b:
+    x -= 2;
+    if (x > 0) goto a;
+    return x;
+}
+
+int irreducible_nested(int n, int x) {
+    for (int i = 0; i < n; i++) {
+        if (x & 1) goto b;
+    a:
+        x += 3;
+    b:
+        x -= 1;
+        if (x % 5 == 0) goto a;
+    }
+    return x;
+}
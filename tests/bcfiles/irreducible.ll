@@ -0,0 +1,69 @@
+; ModuleID = 'irreducible.c'
+source_filename = "irreducible.c"
+target datalayout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128"
+target triple = "x86_64-pc-linux-gnu"
+
+; Function Attrs: nofree norecurse nosync nounwind readnone uwtable
+define dso_local i32 @irreducible_goto(i32 noundef %0) local_unnamed_addr #0 {
+  %2 = icmp eq i32 %0, 0
+  br i1 %2, label %3, label %6
+
+3:                                                ; preds = %1, %6
+  %4 = phi i32 [ %0, %1 ], [ %8, %6 ]
+  %5 = add nsw i32 %4, -1
+  br label %6
+
+6:                                                ; preds = %1, %3
+  %7 = phi i32 [ %0, %1 ], [ %5, %3 ]
+  %8 = add nsw i32 %7, -2
+  %9 = icmp sgt i32 %8, 0
+  br i1 %9, label %3, label %10
+
+10:                                               ; preds = %6
+  ret i32 %8
+}
+
+; Function Attrs: nofree norecurse nosync nounwind readnone uwtable
+define dso_local i32 @irreducible_nested(i32 noundef %0, i32 noundef %1) local_unnamed_addr #0 {
+  %3 = icmp sgt i32 %0, 0
+  br i1 %3, label %4, label %20
+
+4:                                                ; preds = %2, %17
+  %5 = phi i32 [ %18, %17 ], [ 0, %2 ]
+  %6 = phi i32 [ %14, %17 ], [ %1, %2 ]
+  %7 = and i32 %6, 1
+  %8 = icmp eq i32 %7, 0
+  br i1 %8, label %9, label %12
+
+9:                                                ; preds = %4, %12
+  %10 = phi i32 [ %6, %4 ], [ %14, %12 ]
+  %11 = add nsw i32 %10, 3
+  br label %12
+
+12:                                               ; preds = %4, %9
+  %13 = phi i32 [ %6, %4 ], [ %11, %9 ]
+  %14 = add nsw i32 %13, -1
+  %15 = srem i32 %14, 5
+  %16 = icmp eq i32 %15, 0
+  br i1 %16, label %9, label %17
+
+17:                                               ; preds = %12
+  %18 = add nuw nsw i32 %5, 1
+  %19 = icmp eq i32 %18, %0
+  br i1 %19, label %20, label %4
+
+20:                                               ; preds = %2, %17
+  %21 = phi i32 [ %1, %2 ], [ %14, %17 ]
+  ret i32 %21
+}
+
+attributes #0 = { nofree norecurse nosync nounwind readnone uwtable "frame-pointer"="none" "min-legal-vector-width"="0" "no-trapping-math"="true" "stack-protector-buffer-size"="8" "target-cpu"="x86-64" "target-features"="+cx8,+fxsr,+mmx,+sse,+sse2,+x87" "tune-cpu"="generic" }
+
+!llvm.module.flags = !{!0, !1, !2, !3}
+!llvm.ident = !{!4}
+
+!0 = !{i32 1, !"wchar_size", i32 4}
+!1 = !{i32 7, !"PIC Level", i32 2}
+!2 = !{i32 7, !"PIE Level", i32 2}
+!3 = !{i32 7, !"uwtable", i32 1}
+!4 = !{!"clang version 14.0.6"}
@@ -1,5 +1,5 @@
 use itertools::Itertools;
-use llvm_ir::Module;
+use llvm_ir::{BasicBlock, Instruction, Module, Name, Terminator};
 use llvm_ir_analysis::*;
 
 fn init_logging() {
@@ -11,9 +11,16 @@ fn init_logging() {
 /// crossmod.bc are all taken from [`haybale`]'s test suite
 ///
 /// [`haybale`]: https://crates.io/crates/haybale
-const CALL_BC_PATH: &'static str = "tests/bcfiles/call.bc";
-const FUNCTIONPTR_BC_PATH: &'static str = "tests/bcfiles/functionptr.bc";
-const CROSSMOD_BC_PATH: &'static str = "tests/bcfiles/crossmod.bc";
+const CALL_BC_PATH: &str = "tests/bcfiles/call.bc";
+const FUNCTIONPTR_BC_PATH: &str = "tests/bcfiles/functionptr.bc";
+const CROSSMOD_BC_PATH: &str = "tests/bcfiles/crossmod.bc";
+/// bitcast.bc was generated by clang 14, so only LLVM 14 or later can read it
+#[cfg(feature = "llvm-14-or-greater")]
+const BITCAST_BC_PATH: &str = "tests/bcfiles/bitcast.bc";
+/// addrtaken.bc was generated by clang 14, so only LLVM 14 or later can read
+/// it
+#[cfg(feature = "llvm-14-or-greater")]
+const ADDRTAKEN_BC_PATH: &str = "tests/bcfiles/addrtaken.bc";
 
 #[test]
 fn call_graph() {
@@ -215,6 +222,267 @@ fn call_graph() {
     assert_eq!(callees, vec!["mutually_recursive_a"]);
 }
 
+#[test]
+fn call_sites() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let bb1_name = Name::from(1);
+    let sites: Vec<&CallSite> = callgraph
+        .call_sites("simple_caller", "simple_callee")
+        .unwrap()
+        .collect();
+    assert_eq!(
+        sites,
+        vec![&CallSite {
+            caller: "simple_caller",
+            block: &bb1_name,
+            index: 0,
+            inst_kind: CallInstKind::Call,
+            edge_kind: CallEdgeKind::Direct,
+        }]
+    );
+
+    // both calls in `twice_caller` are recorded
+    let indices: Vec<usize> = callgraph
+        .call_sites("twice_caller", "simple_callee")
+        .unwrap()
+        .map(|site| site.index)
+        .collect();
+    assert_eq!(indices, vec![0, 1]);
+    let sites: Vec<(&str, usize)> = callgraph
+        .call_sites_in("twice_caller")
+        .unwrap()
+        .map(|(callee, site)| (callee, site.index))
+        .collect();
+    assert_eq!(sites, vec![("simple_callee", 0), ("simple_callee", 1)]);
+
+    let callers: Vec<&str> = callgraph
+        .call_sites_of("simple_callee")
+        .unwrap()
+        .map(|site| site.caller)
+        .sorted()
+        .collect();
+    assert_eq!(
+        callers,
+        vec![
+            "caller_with_loop",
+            "conditional_caller",
+            "recursive_and_normal_caller",
+            "simple_caller",
+            "twice_caller",
+            "twice_caller"
+        ]
+    );
+
+    assert_eq!(
+        callgraph
+            .call_sites("simple_callee", "simple_caller")
+            .unwrap()
+            .count(),
+        0
+    );
+    assert!(callgraph
+        .call_sites("simple_caller", "nonexistent")
+        .is_err());
+    assert!(callgraph.call_sites_in("nonexistent").is_err());
+    assert!(callgraph.call_sites_of("nonexistent").is_err());
+}
+
+#[test]
+fn call_graph_sccs() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    assert_eq!(
+        callgraph.scc_of("mutually_recursive_a").unwrap(),
+        &["mutually_recursive_a", "mutually_recursive_b"]
+    );
+    assert_eq!(
+        callgraph.scc_of("simple_caller").unwrap(),
+        &["simple_caller"]
+    );
+    assert!(callgraph.scc_of("nonexistent").is_err());
+
+    assert!(callgraph.is_recursive("recursive_simple").unwrap());
+    assert!(callgraph
+        .is_recursive("recursive_and_normal_caller")
+        .unwrap());
+    assert!(callgraph.is_recursive("mutually_recursive_b").unwrap());
+    assert!(!callgraph.is_recursive("simple_caller").unwrap());
+    assert!(!callgraph.is_recursive("simple_callee").unwrap());
+    assert!(callgraph.is_recursive("nonexistent").is_err());
+
+    let cycles: Vec<&[&str]> = callgraph.recursive_cycles().sorted().collect();
+    assert_eq!(
+        cycles,
+        vec![
+            &["mutually_recursive_a", "mutually_recursive_b"][..],
+            &["recursive_and_normal_caller"],
+            &["recursive_double"],
+            &["recursive_not_tail"],
+            &["recursive_simple"],
+        ]
+    );
+
+    // every function (including called declarations) is in exactly one SCC
+    let mut funcs: Vec<&str> = callgraph.sccs().flatten().copied().collect();
+    funcs.sort_unstable();
+    let expected: Vec<&str> = module
+        .functions
+        .iter()
+        .map(|f| f.name.as_str())
+        .chain(module.func_declarations.iter().map(|d| d.name.as_str()))
+        .sorted()
+        .collect();
+    assert_eq!(funcs, expected);
+
+    // in bottom-up order, callees come before their callers (except within
+    // an SCC); top-down order is the reverse
+    let bottom_up: Vec<&[&str]> = callgraph.bottom_up_order().collect();
+    let position = |func_name: &str| {
+        bottom_up
+            .iter()
+            .position(|scc| scc.contains(&func_name))
+            .unwrap()
+    };
+    for func in &module.functions {
+        for callee in callgraph.callees(&func.name).unwrap() {
+            assert!(position(callee) <= position(&func.name));
+        }
+    }
+    assert!(position("simple_callee") < position("simple_caller"));
+    assert!(position("simple_caller") < position("nested_caller"));
+    let top_down: Vec<&[&str]> = callgraph.top_down_order().collect();
+    assert_eq!(
+        top_down,
+        bottom_up.iter().rev().copied().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn call_graph_reachability() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let reachable: Vec<&str> = callgraph
+        .reachable_from(vec!["nested_caller"])
+        .unwrap()
+        .into_iter()
+        .sorted()
+        .collect();
+    assert_eq!(
+        reachable,
+        vec!["nested_caller", "simple_callee", "simple_caller"]
+    );
+    let reachable: Vec<&str> = callgraph
+        .reachable_from(vec!["mutually_recursive_a", "caller_of_loop"])
+        .unwrap()
+        .into_iter()
+        .sorted()
+        .collect();
+    assert_eq!(
+        reachable,
+        vec![
+            "callee_with_loop",
+            "caller_of_loop",
+            "llvm.lifetime.end.p0i8",
+            "llvm.lifetime.start.p0i8",
+            "mutually_recursive_a",
+            "mutually_recursive_b"
+        ]
+    );
+    assert!(callgraph.reachable_from(vec!["nonexistent"]).is_err());
+
+    let can_reach: Vec<&str> = callgraph
+        .can_reach(vec!["simple_caller"])
+        .unwrap()
+        .into_iter()
+        .sorted()
+        .collect();
+    assert_eq!(can_reach, vec!["nested_caller", "simple_caller"]);
+    let can_reach: Vec<&str> = callgraph
+        .can_reach(vec!["mutually_recursive_b"])
+        .unwrap()
+        .into_iter()
+        .sorted()
+        .collect();
+    assert_eq!(
+        can_reach,
+        vec!["mutually_recursive_a", "mutually_recursive_b"]
+    );
+    assert!(callgraph.can_reach(vec!["nonexistent"]).is_err());
+
+    assert_eq!(
+        callgraph
+            .shortest_call_chain("nested_caller", "simple_callee")
+            .unwrap(),
+        Some(vec!["nested_caller", "simple_caller", "simple_callee"])
+    );
+    assert_eq!(
+        callgraph
+            .shortest_call_chain("simple_caller", "simple_caller")
+            .unwrap(),
+        Some(vec!["simple_caller"])
+    );
+    assert_eq!(
+        callgraph
+            .shortest_call_chain("simple_callee", "simple_caller")
+            .unwrap(),
+        None
+    );
+    assert!(callgraph
+        .shortest_call_chain("nested_caller", "nonexistent")
+        .is_err());
+
+    let sites = callgraph
+        .shortest_call_chain_sites("nested_caller", "simple_callee")
+        .unwrap()
+        .unwrap();
+    assert_eq!(sites.len(), 2);
+    assert_eq!(sites[0].caller, "nested_caller");
+    assert_eq!(sites[1].caller, "simple_caller");
+    assert!(sites
+        .iter()
+        .all(|site| site.edge_kind == CallEdgeKind::Direct));
+    assert_eq!(
+        callgraph
+            .shortest_call_chain_sites("simple_caller", "simple_caller")
+            .unwrap(),
+        Some(vec![])
+    );
+}
+
+#[test]
+fn call_graph_dot() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let mut options = DotOptions::default();
+    options.highlight_functions.insert("simple_caller");
+    let dot = callgraph.to_dot(&options);
+    assert!(dot.starts_with("digraph \"call graph\" {\n"));
+    assert!(dot.contains(
+        "  \"simple_caller\" [label=\"simple_caller\", style=filled, fillcolor=yellow];\n"
+    ));
+    assert!(dot.contains("  \"simple_callee\" [label=\"simple_callee\"];\n"));
+    assert!(dot.contains("  \"simple_caller\" -> \"simple_callee\";\n"));
+    assert!(dot.contains("  \"nested_caller\" -> \"simple_caller\";\n"));
+    assert!(dot.contains("  \"recursive_simple\" -> \"recursive_simple\";\n"));
+}
+
 #[test]
 fn functionptr_call_graph() {
     init_logging();
@@ -298,6 +566,111 @@ fn functionptr_call_graph() {
     );
 }
 
+#[test]
+fn functionptr_call_sites() {
+    init_logging();
+    let module = Module::from_bc_path(FUNCTIONPTR_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    // the call through the function pointer may go to either `foo` or `bar`
+    let sites: Vec<(&str, &CallSite)> = callgraph
+        .call_sites_in("calls_fptr")
+        .unwrap()
+        .sorted_by_key(|(callee, _)| *callee)
+        .collect();
+    assert_eq!(sites.len(), 2);
+    assert_eq!(sites[0].0, "bar");
+    assert_eq!(sites[1].0, "foo");
+    assert_eq!(sites[0].1, sites[1].1);
+    assert_eq!(sites[0].1.edge_kind, CallEdgeKind::FunctionPointer);
+    assert_eq!(sites[0].1.inst_kind, CallInstKind::Call);
+
+    let kinds: Vec<CallEdgeKind> = callgraph
+        .call_sites("fptr_driver", "get_function_ptr")
+        .unwrap()
+        .map(|site| site.edge_kind)
+        .collect();
+    assert_eq!(kinds, vec![CallEdgeKind::Direct]);
+}
+
+#[test]
+#[cfg(feature = "llvm-14-or-greater")]
+fn bitcast_call_sites() {
+    init_logging();
+    let module = Module::from_bc_path(BITCAST_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let bb1_name = Name::from(1);
+    let sites: Vec<&CallSite> = callgraph
+        .call_sites("bitcast_caller", "bitcast_callee")
+        .unwrap()
+        .collect();
+    assert_eq!(
+        sites,
+        vec![&CallSite {
+            caller: "bitcast_caller",
+            block: &bb1_name,
+            index: 3,
+            inst_kind: CallInstKind::Call,
+            edge_kind: CallEdgeKind::BitcastDirect,
+        }]
+    );
+
+    let dot = callgraph.to_dot(&DotOptions {
+        edge_kinds: true,
+        ..Default::default()
+    });
+    assert!(dot.contains("  \"bitcast_caller\" -> \"bitcast_callee\" [label=\"bitcast\"];\n"));
+}
+
+#[test]
+#[cfg(feature = "llvm-14-or-greater")]
+fn address_taken_functions() {
+    init_logging();
+    let module = Module::from_bc_path(ADDRTAKEN_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let address_taken = analysis.address_taken_functions();
+
+    let functions: Vec<&str> = address_taken.functions().sorted().collect();
+    assert_eq!(functions, vec!["in_table", "taken"]);
+    assert!(address_taken.is_address_taken("taken"));
+    assert!(address_taken.is_address_taken("in_table"));
+    assert!(!address_taken.is_address_taken("not_taken"));
+    assert!(!address_taken.is_address_taken("calls_fptr"));
+}
+
+#[test]
+#[cfg(feature = "llvm-14-or-greater")]
+fn address_taken_call_graph() {
+    init_logging();
+    let module = Module::from_bc_path(ADDRTAKEN_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    // by default, only functions whose address is taken are targets of the
+    // call through the function pointer
+    let callgraph = analysis.call_graph();
+    let callees: Vec<&str> = callgraph.callees("calls_fptr").unwrap().sorted().collect();
+    assert_eq!(callees, vec!["in_table", "taken"]);
+    let callers: Vec<&str> = callgraph.callers("not_taken").unwrap().sorted().collect();
+    assert_eq!(callers, vec!["addrtaken_driver"]);
+
+    // with `MatchingType`, any function of the appropriate type is a target
+    let callgraph = analysis.call_graph_with(IndirectCallTargets::MatchingType);
+    let callees: Vec<&str> = callgraph.callees("calls_fptr").unwrap().sorted().collect();
+    assert_eq!(
+        callees,
+        vec!["addrtaken_driver", "in_table", "not_taken", "taken"]
+    );
+    let callers: Vec<&str> = callgraph.callers("not_taken").unwrap().sorted().collect();
+    assert_eq!(callers, vec!["addrtaken_driver", "calls_fptr"]);
+}
+
 #[test]
 fn crossmod_call_graph() {
     init_logging();
@@ -362,3 +735,276 @@ fn crossmod_call_graph() {
         .collect();
     assert!(callees.is_empty());
 }
+
+#[test]
+fn crossmod_fallible_lookups() {
+    init_logging();
+    let call_module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let crossmod_module = Module::from_bc_path(CROSSMOD_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let modules = [call_module, crossmod_module];
+    let analysis = CrossModuleAnalysis::new(&modules);
+
+    let module_analysis = analysis
+        .try_module_analysis(&modules[0].name)
+        .expect("module should be found");
+    assert!(module_analysis.try_fn_analysis("simple_caller").is_ok());
+    assert!(matches!(
+        module_analysis.try_fn_analysis("nonexistent"),
+        Err(Error::FunctionNotFound { name }) if name == "nonexistent"
+    ));
+    assert!(matches!(
+        analysis.try_module_analysis("nonexistent"),
+        Err(Error::ModuleNotFound { name }) if name == "nonexistent"
+    ));
+
+    let (func, module) = analysis
+        .try_get_func_by_name("simple_caller")
+        .unwrap()
+        .expect("function should be found");
+    assert_eq!(func.name, "simple_caller");
+    assert_eq!(module.name, modules[0].name);
+    assert!(analysis
+        .try_get_func_by_name("nonexistent")
+        .unwrap()
+        .is_none());
+
+    // with the same module twice, every function name is duplicated
+    let duplicated = CrossModuleAnalysis::new([&modules[0], &modules[0]]);
+    match duplicated.try_get_func_by_name("simple_caller") {
+        Ok(_) => panic!("function name should be duplicated"),
+        Err(e) => assert_eq!(
+            e,
+            Error::DuplicateSymbol {
+                name: "simple_caller".into(),
+                first_module: modules[0].name.clone(),
+                second_module: modules[0].name.clone(),
+            }
+        ),
+    }
+}
+
+#[test]
+fn call_graph_errors() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let err = match callgraph.callers("nonexistent") {
+        Ok(_) => panic!("function should not be found"),
+        Err(e) => e,
+    };
+    assert_eq!(
+        err,
+        Error::FunctionNotFound {
+            name: "nonexistent".into()
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "Function named \"nonexistent\" not found in the Module(s)"
+    );
+
+    // our `Error` works with `?` in functions returning boxed errors
+    fn callees(
+        callgraph: &CallGraph,
+        func_name: &str,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        Ok(callgraph
+            .callees(func_name)?
+            .sorted()
+            .map(str::to_owned)
+            .collect())
+    }
+    assert_eq!(
+        callees(callgraph, "simple_caller").unwrap(),
+        vec!["simple_callee"]
+    );
+    assert!(callees(callgraph, "nonexistent").is_err());
+}
+
+#[test]
+fn replace_function() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let get_func = |func_name: &str| {
+        module
+            .get_func_by_name(func_name)
+            .unwrap_or_else(|| panic!("Failed to find function {:?}", func_name))
+    };
+
+    // edit a copy of `simple_caller`, so that after calling `simple_callee`
+    // it branches to a new block, which also calls `callee_with_loop`
+    let mut edited = get_func("simple_caller").clone();
+    let new_block_name = Name::from("new_block");
+    let mut call = get_func("caller_of_loop").basic_blocks[0].instrs[0].clone();
+    match &mut call {
+        Instruction::Call(call) => call.dest = Some(Name::from("loop_result")),
+        inst => panic!("Expected a call, got {}", inst),
+    }
+    let mut br = get_func("conditional_caller").basic_blocks[1].term.clone();
+    match &mut br {
+        Terminator::Br(br) => br.dest = new_block_name.clone(),
+        term => panic!("Expected a br, got {}", term),
+    }
+    let mut new_block = BasicBlock::new(new_block_name.clone());
+    new_block.instrs.push(call);
+    new_block.term = std::mem::replace(&mut edited.basic_blocks[0].term, br);
+    edited.basic_blocks.push(new_block);
+    let entry_name = edited.basic_blocks[0].name.clone();
+
+    let mut renamed = edited.clone();
+    renamed.name = "nonexistent".into();
+
+    let mut analysis = ModuleAnalysis::new(&module);
+    assert_eq!(
+        analysis
+            .call_graph()
+            .callees("simple_caller")
+            .unwrap()
+            .collect::<Vec<_>>(),
+        vec!["simple_callee"]
+    );
+    assert_eq!(
+        analysis
+            .fn_analysis("simple_caller")
+            .control_flow_graph()
+            .succs(&entry_name)
+            .collect::<Vec<_>>(),
+        vec![CFGNode::Return]
+    );
+    analysis.fn_analysis("caller_of_loop").control_flow_graph();
+
+    analysis
+        .replace_function(&edited)
+        .unwrap_or_else(|e| panic!("{}", e));
+
+    // the analyses of the replaced function are discarded, but not those of
+    // the other functions
+    assert!(!analysis
+        .fn_analysis("simple_caller")
+        .is_computed(AnalysisKind::ControlFlowGraph));
+    assert!(analysis
+        .fn_analysis("caller_of_loop")
+        .is_computed(AnalysisKind::ControlFlowGraph));
+
+    // the module-wide analyses see the edited function
+    let callgraph = analysis.call_graph();
+    assert_eq!(
+        callgraph
+            .callees("simple_caller")
+            .unwrap()
+            .sorted()
+            .collect::<Vec<_>>(),
+        vec!["callee_with_loop", "simple_callee"]
+    );
+    assert_eq!(
+        callgraph
+            .callers("callee_with_loop")
+            .unwrap()
+            .sorted()
+            .collect::<Vec<_>>(),
+        vec!["caller_of_loop", "simple_caller"]
+    );
+    assert!(analysis.functions().any(|f| std::ptr::eq(f, &edited)));
+
+    // and so does the analysis of the edited function
+    let cfg = analysis.fn_analysis("simple_caller").control_flow_graph();
+    assert_eq!(
+        cfg.succs(&entry_name).collect::<Vec<_>>(),
+        vec![CFGNode::Block(&new_block_name)]
+    );
+    assert_eq!(
+        cfg.succs(&new_block_name).collect::<Vec<_>>(),
+        vec![CFGNode::Return]
+    );
+
+    assert_eq!(
+        analysis.replace_function(&renamed),
+        Err(Error::FunctionNotFound {
+            name: "nonexistent".into()
+        })
+    );
+}
+
+#[test]
+fn add_function() {
+    init_logging();
+    let module = Module::from_bc_path(CALL_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let crossmod_module = Module::from_bc_path(CROSSMOD_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+
+    // a new function which, like `caller_of_loop`, calls `callee_with_loop`
+    let mut new_func = module
+        .get_func_by_name("caller_of_loop")
+        .expect("Failed to find function")
+        .clone();
+    new_func.name = "new_caller".into();
+
+    let mut analysis = ModuleAnalysis::new(&module);
+    analysis.fn_analysis("caller_of_loop").control_flow_graph();
+    assert!(analysis.try_fn_analysis("new_caller").is_err());
+
+    analysis
+        .add_function(&new_func)
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert!(analysis
+        .fn_analysis("caller_of_loop")
+        .is_computed(AnalysisKind::ControlFlowGraph));
+    assert!(analysis.functions().any(|f| std::ptr::eq(f, &new_func)));
+    assert_eq!(
+        analysis
+            .call_graph()
+            .callers("callee_with_loop")
+            .unwrap()
+            .sorted()
+            .collect::<Vec<_>>(),
+        vec!["caller_of_loop", "new_caller"]
+    );
+    analysis.fn_analysis("new_caller").control_flow_graph();
+    assert_eq!(
+        analysis.add_function(&new_func),
+        Err(Error::DuplicateSymbol {
+            name: "new_caller".into(),
+            first_module: module.name.clone(),
+            second_module: module.name.clone(),
+        })
+    );
+
+    let mut analysis = CrossModuleAnalysis::new(vec![&module, &crossmod_module]);
+    analysis
+        .add_function(&crossmod_module.name, &new_func)
+        .unwrap_or_else(|e| panic!("{}", e));
+    let (_, found_in) = analysis
+        .get_func_by_name("new_caller")
+        .expect("Failed to find new function");
+    assert_eq!(found_in.name, crossmod_module.name);
+    assert_eq!(
+        analysis
+            .call_graph()
+            .callers("callee_with_loop")
+            .unwrap()
+            .sorted()
+            .collect::<Vec<_>>(),
+        vec!["caller_of_loop", "new_caller"]
+    );
+    assert_eq!(
+        analysis.add_function(&module.name, &new_func),
+        Err(Error::DuplicateSymbol {
+            name: "new_caller".into(),
+            first_module: crossmod_module.name.clone(),
+            second_module: module.name.clone(),
+        })
+    );
+    assert_eq!(
+        analysis.add_function("nonexistent", &new_func),
+        Err(Error::ModuleNotFound {
+            name: "nonexistent".into()
+        })
+    );
+}
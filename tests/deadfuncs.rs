@@ -0,0 +1,63 @@
+// deadfuncs.bc was generated by clang 14, so only LLVM 14 or later can read it
+#![cfg(feature = "llvm-14-or-greater")]
+
+use itertools::Itertools;
+use llvm_ir::Module;
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+const DEADFUNCS_BC_PATH: &str = "tests/bcfiles/deadfuncs.bc";
+
+#[test]
+fn dead_functions_from_named() {
+    init_logging();
+    let module = Module::from_bc_path(DEADFUNCS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    let dead = analysis
+        .dead_functions(EntryPoints::Named(&["main"]))
+        .unwrap();
+    let dead_functions: Vec<&str> = dead.dead_functions().collect();
+    assert_eq!(
+        dead_functions,
+        vec![
+            "dead_callee",
+            "dead_recursive",
+            "dead_static",
+            "only_from_exported"
+        ]
+    );
+    assert!(dead.is_dead("dead_static"));
+    assert!(!dead.is_dead("used_static"));
+    // address-taken functions are live
+    assert!(!dead.is_dead("taken_static"));
+    // externally visible functions are never reported, even if unreachable
+    assert!(!dead.is_dead("exported"));
+
+    assert!(analysis
+        .dead_functions(EntryPoints::Named(&["nonexistent"]))
+        .is_err());
+}
+
+#[test]
+fn dead_functions_from_externally_visible() {
+    init_logging();
+    let module = Module::from_bc_path(DEADFUNCS_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = CrossModuleAnalysis::new(std::iter::once(&module));
+
+    let dead = analysis
+        .dead_functions(EntryPoints::ExternallyVisible)
+        .unwrap();
+    let dead_functions: Vec<&str> = dead.dead_functions().sorted().collect();
+    assert_eq!(
+        dead_functions,
+        vec!["dead_callee", "dead_recursive", "dead_static"]
+    );
+    assert!(!dead.is_dead("only_from_exported"));
+}
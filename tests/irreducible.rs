@@ -0,0 +1,124 @@
+use itertools::Itertools;
+use llvm_ir::{Module, Name};
+use llvm_ir_analysis::*;
+
+fn init_logging() {
+    // capture log messages with test harness
+    let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// irreducible.bc was generated by clang 14, so only LLVM 14 or later can
+/// read it
+#[cfg(feature = "llvm-14-or-greater")]
+const IRREDUCIBLE_BC_PATH: &str = "tests/bcfiles/irreducible.bc";
+const LOOP_BC_PATH: &str = "tests/bcfiles/loop.bc";
+
+#[test]
+fn loops_are_reducible() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    for func in &module.functions {
+        let loop_info = analysis.fn_analysis(&func.name).loop_info();
+        assert!(
+            loop_info.is_reducible(),
+            "expected {} to be reducible",
+            &func.name
+        );
+        assert_eq!(loop_info.irreducible_edges().count(), 0);
+        assert_eq!(loop_info.irreducible_regions().count(), 0);
+    }
+}
+
+#[test]
+#[cfg(feature = "llvm-14-or-greater")]
+fn irreducible_goto() {
+    init_logging();
+    let module = Module::from_bc_path(IRREDUCIBLE_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let loop_info = analysis.fn_analysis("irreducible_goto").loop_info();
+
+    // CFG:
+    //   1
+    //   | \
+    //   3  |
+    //   | ^|
+    //   |/ |
+    //   6 <
+    //   |
+    //  10
+    //
+    // 3 and 6 form a cycle which can be entered at either block
+
+    let bb3_name = Name::from(3);
+    let bb6_name = Name::from(6);
+
+    assert!(!loop_info.is_reducible());
+    assert_eq!(loop_info.loops().count(), 0);
+
+    let edges: Vec<(&Name, &Name)> = loop_info.irreducible_edges().collect();
+    assert_eq!(edges.len(), 1);
+    assert!(edges[0] == (&bb3_name, &bb6_name) || edges[0] == (&bb6_name, &bb3_name));
+
+    let regions: Vec<&IrreducibleRegion> = loop_info.irreducible_regions().collect();
+    assert_eq!(regions.len(), 1);
+    let blocks: Vec<&Name> = regions[0].blocks().collect();
+    assert_eq!(blocks, vec![&bb3_name, &bb6_name]);
+    let entries: Vec<&Name> = regions[0].entries().collect();
+    assert_eq!(entries, vec![&bb3_name, &bb6_name]);
+}
+
+#[test]
+#[cfg(feature = "llvm-14-or-greater")]
+fn irreducible_nested() {
+    init_logging();
+    let module = Module::from_bc_path(IRREDUCIBLE_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let loop_info = analysis.fn_analysis("irreducible_nested").loop_info();
+
+    // CFG:
+    //   2
+    //   | \
+    //   4 <----------
+    //   | \          \
+    //   9  |          |
+    //   | ^|          |
+    //   |/ |          |
+    //  12 <           |
+    //   |             |
+    //  17 ---------->
+    //   | /
+    //  20
+    //
+    // 4 is the header of a natural loop, but inside it, 9 and 12 form a cycle
+    // which can be entered at either block
+
+    let bb4_name = Name::from(4);
+    let bb9_name = Name::from(9);
+    let bb12_name = Name::from(12);
+    let bb17_name = Name::from(17);
+
+    assert!(!loop_info.is_reducible());
+
+    // the outer loop is still a natural loop
+    let headers: Vec<&Name> = loop_info.loops().map(|l| l.header()).collect();
+    assert_eq!(headers, vec![&bb4_name]);
+    let outer = loop_info.loop_with_header(&bb4_name).unwrap();
+    let blocks: Vec<&Name> = outer.blocks().sorted().collect();
+    assert_eq!(blocks, vec![&bb4_name, &bb9_name, &bb12_name, &bb17_name]);
+    assert_eq!(loop_info.loop_depth(&bb9_name), 1);
+
+    let edges: Vec<(&Name, &Name)> = loop_info.irreducible_edges().collect();
+    assert_eq!(edges.len(), 1);
+    assert!(edges[0] == (&bb9_name, &bb12_name) || edges[0] == (&bb12_name, &bb9_name));
+
+    let regions: Vec<&IrreducibleRegion> = loop_info.irreducible_regions().collect();
+    assert_eq!(regions.len(), 1);
+    let blocks: Vec<&Name> = regions[0].blocks().collect();
+    assert_eq!(blocks, vec![&bb9_name, &bb12_name]);
+    let entries: Vec<&Name> = regions[0].entries().collect();
+    assert_eq!(entries, vec![&bb9_name, &bb12_name]);
+}
@@ -1,6 +1,9 @@
+#![allow(clippy::bool_assert_comparison)]
+
 use itertools::Itertools;
-use llvm_ir::{Module, Name};
+use llvm_ir::{BasicBlock, Instruction, Module, Name, Terminator};
 use llvm_ir_analysis::*;
+use std::collections::{BTreeSet, HashSet};
 
 fn init_logging() {
     // capture log messages with test harness
@@ -10,7 +13,7 @@ fn init_logging() {
 /// loop.c and loop.bc are taken from [`haybale`]'s test suite
 ///
 /// [`haybale`]: https://crates.io/crates/haybale
-const LOOP_BC_PATH: &'static str = "tests/bcfiles/loop.bc";
+const LOOP_BC_PATH: &str = "tests/bcfiles/loop.bc";
 
 #[test]
 fn while_loop_cfg() {
@@ -90,6 +93,49 @@ fn for_loop_cfg() {
     assert_eq!(bb9_succs, vec![bb6_node, bb9_node]);
 }
 
+#[test]
+fn for_loop_traversal_orders() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let cfg = analysis.fn_analysis("for_loop").control_flow_graph();
+
+    let bb1_name = Name::from(1);
+    let bb1_node = CFGNode::Block(&bb1_name);
+    let bb6_name = Name::from(6);
+    let bb6_node = CFGNode::Block(&bb6_name);
+    let bb9_name = Name::from(9);
+    let bb9_node = CFGNode::Block(&bb9_name);
+
+    // the loop body 9 must come before the exit 6, since 9 -> 6 is not a back
+    // edge
+    let rpo: Vec<CFGNode> = cfg.reverse_postorder().collect();
+    assert_eq!(rpo, vec![bb1_node, bb9_node, bb6_node, CFGNode::Return]);
+    let postorder: Vec<CFGNode> = cfg.postorder().collect();
+    assert_eq!(
+        postorder,
+        vec![CFGNode::Return, bb6_node, bb9_node, bb1_node]
+    );
+
+    let preorder: Vec<CFGNode> = cfg.preorder().collect();
+    assert_eq!(preorder[0], bb1_node);
+    let preorder: Vec<CFGNode> = preorder.into_iter().sorted().collect();
+    assert_eq!(
+        preorder,
+        vec![bb1_node, bb6_node, bb9_node, CFGNode::Return]
+    );
+
+    assert_eq!(cfg.rpo_number(&bb1_name), Some(0));
+    assert_eq!(cfg.rpo_number(&bb9_name), Some(1));
+    assert_eq!(cfg.rpo_number(&bb6_name), Some(2));
+    assert_eq!(cfg.rpo_number(&Name::from("nonexistent")), None);
+
+    let reachable: Vec<&Name> = cfg.reachable_blocks().collect();
+    assert_eq!(reachable, vec![&bb1_name, &bb9_name, &bb6_name]);
+    assert_eq!(cfg.unreachable_blocks().count(), 0);
+}
+
 #[test]
 fn loop_zero_iterations_cfg() {
     init_logging();
@@ -687,6 +733,309 @@ fn nested_loop_domtree() {
     );
 }
 
+#[test]
+fn nested_loop_nearest_common_dominators() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("nested_loop");
+
+    // See CFG in `nested_loop_domtree` above
+    let bb1_name = Name::from(1);
+    let bb1_node = CFGNode::Block(&bb1_name);
+    let bb5_name = Name::from(5);
+    let bb5_node = CFGNode::Block(&bb5_name);
+    let bb7_name = Name::from(7);
+    let bb7_node = CFGNode::Block(&bb7_name);
+    let bb10_name = Name::from(10);
+    let bb10_node = CFGNode::Block(&bb10_name);
+    let bb13_name = Name::from(13);
+    let bb13_node = CFGNode::Block(&bb13_name);
+
+    let domtree = fn_analysis.dominator_tree();
+    assert!(domtree.dominates(bb1_node, bb10_node));
+    assert!(domtree.dominates(bb5_node, bb10_node));
+    assert!(domtree.dominates(bb10_node, bb10_node));
+    assert!(!domtree.strictly_dominates(bb10_node, bb10_node));
+    assert!(!domtree.dominates(bb7_node, bb10_node));
+    assert!(!domtree.dominates(bb10_node, bb5_node));
+    assert!(domtree.dominates(bb7_node, CFGNode::Return));
+    assert_eq!(
+        domtree.nearest_common_dominator(bb10_node, bb13_node),
+        Some(bb13_node)
+    );
+    assert_eq!(
+        domtree.nearest_common_dominator(bb10_node, bb7_node),
+        Some(bb1_node)
+    );
+    assert_eq!(
+        domtree.nearest_common_dominator(bb13_node, CFGNode::Return),
+        Some(bb1_node)
+    );
+
+    let postdomtree = fn_analysis.postdominator_tree();
+    assert!(postdomtree.postdominates(bb7_node, bb1_node));
+    assert!(postdomtree.postdominates(bb10_node, bb5_node));
+    assert!(postdomtree.postdominates(CFGNode::Return, bb13_node));
+    assert!(!postdomtree.postdominates(bb13_node, bb1_node));
+    assert!(!postdomtree.strictly_postdominates(bb7_node, bb7_node));
+    assert_eq!(
+        postdomtree.nearest_common_postdominator(bb1_node, bb5_node),
+        Some(bb7_node)
+    );
+    assert_eq!(
+        postdomtree.nearest_common_postdominator(bb5_node, bb13_node),
+        Some(bb13_node)
+    );
+    assert_eq!(
+        postdomtree.nearest_common_postdominator(bb7_node, CFGNode::Return),
+        Some(CFGNode::Return)
+    );
+}
+
+#[test]
+fn loop_zero_iterations_frontiers() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("loop_zero_iterations");
+
+    // CFG:
+    //   1
+    //   | \
+    //   |  5     _
+    //   |  | \ /   \
+    //   |  | 11 - /
+    //   |  | /
+    //   |  8
+    //   | /
+    //  18
+
+    let bb1_name = Name::from(1);
+    let bb1_node = CFGNode::Block(&bb1_name);
+    let bb5_name = Name::from(5);
+    let bb5_node = CFGNode::Block(&bb5_name);
+    let bb8_name = Name::from(8);
+    let bb8_node = CFGNode::Block(&bb8_name);
+    let bb11_name = Name::from(11);
+    let bb11_node = CFGNode::Block(&bb11_name);
+    let bb18_name = Name::from(18);
+    let bb18_node = CFGNode::Block(&bb18_name);
+
+    let domtree = fn_analysis.dominator_tree();
+    assert_eq!(domtree.dominance_frontier(&bb1_name).count(), 0);
+    let df: Vec<CFGNode> = domtree.dominance_frontier(&bb5_name).sorted().collect();
+    assert_eq!(df, vec![bb18_node]);
+    let df: Vec<CFGNode> = domtree.dominance_frontier(&bb8_name).sorted().collect();
+    assert_eq!(df, vec![bb18_node]);
+    let df: Vec<CFGNode> = domtree.dominance_frontier(&bb11_name).sorted().collect();
+    assert_eq!(df, vec![bb8_node, bb11_node]);
+    assert_eq!(domtree.dominance_frontier(&bb18_name).count(), 0);
+
+    let idf: Vec<CFGNode> = domtree
+        .iterated_dominance_frontier(vec![&bb11_name])
+        .sorted()
+        .collect();
+    assert_eq!(idf, vec![bb8_node, bb11_node, bb18_node]);
+    let idf: Vec<CFGNode> = domtree
+        .iterated_dominance_frontier(vec![&bb1_name, &bb18_name])
+        .sorted()
+        .collect();
+    assert!(idf.is_empty());
+
+    let postdomtree = fn_analysis.postdominator_tree();
+    assert_eq!(postdomtree.postdominance_frontier(&bb1_name).count(), 0);
+    let pdf: Vec<CFGNode> = postdomtree
+        .postdominance_frontier(&bb5_name)
+        .sorted()
+        .collect();
+    assert_eq!(pdf, vec![bb1_node]);
+    let pdf: Vec<CFGNode> = postdomtree
+        .postdominance_frontier(&bb8_name)
+        .sorted()
+        .collect();
+    assert_eq!(pdf, vec![bb1_node]);
+    let pdf: Vec<CFGNode> = postdomtree
+        .postdominance_frontier(&bb11_name)
+        .sorted()
+        .collect();
+    assert_eq!(pdf, vec![bb5_node, bb11_node]);
+    assert_eq!(postdomtree.postdominance_frontier(&bb18_name).count(), 0);
+
+    let ipdf: Vec<CFGNode> = postdomtree
+        .iterated_postdominance_frontier(vec![&bb11_name])
+        .sorted()
+        .collect();
+    assert_eq!(ipdf, vec![bb1_node, bb5_node, bb11_node]);
+}
+
+#[test]
+fn while_loop_loop_info() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let loop_info = analysis.fn_analysis("while_loop").loop_info();
+
+    // See CFG in `while_loop_cfg` above
+    let bb1_name = Name::from(1);
+    let bb6_name = Name::from(6);
+    let bb12_name = Name::from(12);
+    let bb12_node = CFGNode::Block(&bb12_name);
+
+    assert_eq!(loop_info.loops().count(), 1);
+    let l = loop_info
+        .loop_for(&bb6_name)
+        .expect("bb6 should be in a loop");
+    assert_eq!(l.header(), &bb6_name);
+    assert_eq!(l.blocks().collect::<Vec<_>>(), vec![&bb6_name]);
+    assert_eq!(l.latches().collect::<Vec<_>>(), vec![&bb6_name]);
+    assert_eq!(l.exiting_blocks().collect::<Vec<_>>(), vec![&bb6_name]);
+    assert_eq!(l.exit_blocks().collect::<Vec<_>>(), vec![bb12_node]);
+    assert_eq!(l.preheader(), Some(&bb1_name));
+    assert_eq!(l.depth(), 1);
+    assert!(loop_info.parent_loop(l).is_none());
+
+    assert!(loop_info.loop_for(&bb1_name).is_none());
+    assert!(loop_info.loop_for(&bb12_name).is_none());
+    assert!(loop_info.is_loop_header(&bb6_name));
+    assert!(!loop_info.is_loop_header(&bb1_name));
+    assert_eq!(loop_info.loop_depth(&bb1_name), 0);
+    assert_eq!(loop_info.loop_depth(&bb6_name), 1);
+    let back_edges: Vec<(&Name, &Name)> = loop_info.back_edges().collect();
+    assert_eq!(back_edges, vec![(&bb6_name, &bb6_name)]);
+}
+
+#[test]
+fn search_array_loop_info() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let loop_info = analysis.fn_analysis("search_array").loop_info();
+
+    // See CFG in `search_array_cfg` above
+    let bb1_name = Name::from(1);
+    let bb4_name = Name::from(4);
+    let bb11_name = Name::from(11);
+    let bb11_node = CFGNode::Block(&bb11_name);
+    let bb16_name = Name::from(16);
+    let bb19_name = Name::from(19);
+    let bb19_node = CFGNode::Block(&bb19_name);
+    let bb21_name = Name::from(21);
+    let bb21_node = CFGNode::Block(&bb21_name);
+
+    let headers: Vec<&Name> = loop_info
+        .top_level_loops()
+        .map(|l| l.header())
+        .sorted()
+        .collect();
+    assert_eq!(headers, vec![&bb4_name, &bb11_name]);
+
+    let first = loop_info.loop_with_header(&bb4_name).unwrap();
+    assert_eq!(first.blocks().collect::<Vec<_>>(), vec![&bb4_name]);
+    assert_eq!(first.exit_blocks().collect::<Vec<_>>(), vec![bb11_node]);
+    assert_eq!(first.preheader(), Some(&bb1_name));
+
+    let second = loop_info.loop_with_header(&bb11_name).unwrap();
+    let blocks: Vec<&Name> = second.blocks().sorted().collect();
+    assert_eq!(blocks, vec![&bb11_name, &bb16_name]);
+    assert_eq!(second.latches().collect::<Vec<_>>(), vec![&bb16_name]);
+    let exiting: Vec<&Name> = second.exiting_blocks().collect();
+    assert_eq!(exiting, vec![&bb11_name, &bb16_name]);
+    let exits: Vec<CFGNode> = second.exit_blocks().collect();
+    assert_eq!(exits, vec![bb19_node, bb21_node]);
+    // bb4 may also branch back to itself, so it isn't a preheader
+    assert_eq!(second.preheader(), None);
+
+    assert!(loop_info.loop_for(&bb19_name).is_none());
+    assert!(loop_info.loop_with_header(&bb16_name).is_none());
+}
+
+#[test]
+fn nested_loop_loop_info() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let loop_info = analysis.fn_analysis("nested_loop").loop_info();
+
+    // See CFG in `nested_loop_cfg` above
+    let bb1_name = Name::from(1);
+    let bb5_name = Name::from(5);
+    let bb7_name = Name::from(7);
+    let bb7_node = CFGNode::Block(&bb7_name);
+    let bb10_name = Name::from(10);
+    let bb10_node = CFGNode::Block(&bb10_name);
+    let bb13_name = Name::from(13);
+
+    let headers: Vec<&Name> = loop_info.loops().map(|l| l.header()).collect();
+    assert_eq!(headers, vec![&bb5_name, &bb13_name]);
+
+    let outer = loop_info.loop_with_header(&bb5_name).unwrap();
+    let blocks: Vec<&Name> = outer.blocks().sorted().collect();
+    assert_eq!(blocks, vec![&bb5_name, &bb10_name, &bb13_name]);
+    assert_eq!(outer.latches().collect::<Vec<_>>(), vec![&bb10_name]);
+    assert_eq!(outer.exiting_blocks().collect::<Vec<_>>(), vec![&bb10_name]);
+    assert_eq!(outer.exit_blocks().collect::<Vec<_>>(), vec![bb7_node]);
+    // bb1 may also branch to bb7, so it isn't a preheader
+    assert_eq!(outer.preheader(), None);
+    assert_eq!(outer.depth(), 1);
+    assert!(loop_info.parent_loop(outer).is_none());
+    let subloops: Vec<&Name> = loop_info.subloops(outer).map(|l| l.header()).collect();
+    assert_eq!(subloops, vec![&bb13_name]);
+
+    let inner = loop_info.loop_for(&bb13_name).unwrap();
+    assert_eq!(inner.header(), &bb13_name);
+    assert_eq!(inner.blocks().collect::<Vec<_>>(), vec![&bb13_name]);
+    assert_eq!(inner.latches().collect::<Vec<_>>(), vec![&bb13_name]);
+    assert_eq!(inner.exit_blocks().collect::<Vec<_>>(), vec![bb10_node]);
+    assert_eq!(inner.preheader(), Some(&bb5_name));
+    assert_eq!(inner.depth(), 2);
+    assert_eq!(loop_info.parent_loop(inner).unwrap().header(), &bb5_name);
+    assert!(outer.contains(&bb13_name));
+    assert!(!inner.contains(&bb5_name));
+
+    assert_eq!(loop_info.loop_depth(&bb1_name), 0);
+    assert_eq!(loop_info.loop_depth(&bb5_name), 1);
+    assert_eq!(loop_info.loop_depth(&bb10_name), 1);
+    assert_eq!(loop_info.loop_depth(&bb13_name), 2);
+    assert_eq!(loop_info.loop_depth(&bb7_name), 0);
+    assert_eq!(loop_info.loop_for(&bb10_name).unwrap().header(), &bb5_name);
+}
+
+#[test]
+fn unreachable_loop_info() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let func = module
+        .get_func_by_name("while_loop")
+        .expect("Failed to find function");
+
+    // add an unreachable block to `while_loop` which branches to itself
+    let mut edited = func.clone();
+    let dead_name = Name::from("dead");
+    let mut dead = BasicBlock::new(dead_name.clone());
+    dead.term = edited.basic_blocks[0].term.clone();
+    match &mut dead.term {
+        Terminator::Br(br) => br.dest = dead_name.clone(),
+        term => panic!("Expected a br, got {}", term),
+    }
+    edited.basic_blocks.push(dead);
+
+    let fn_analysis = FunctionAnalysis::new(&edited);
+    let loop_info = fn_analysis.loop_info();
+    let bb6_name = Name::from(6);
+    let headers: Vec<&Name> = loop_info.loops().map(|l| l.header()).collect();
+    assert_eq!(headers, vec![&bb6_name]);
+    assert!(loop_info.loop_for(&dead_name).is_none());
+    assert!(!loop_info.is_loop_header(&dead_name));
+    assert_eq!(loop_info.loop_depth(&dead_name), 0);
+}
+
 #[test]
 fn infinite_loop_cfg() {
     init_logging();
@@ -1150,3 +1499,763 @@ fn infinite_loop_cdg() {
     assert_eq!(cdg.get_imm_control_dependencies(&Name::from(1)).count(), 0);
     assert_eq!(cdg.get_control_dependencies(&Name::from(1)).count(), 0);
 }
+
+/// A custom per-function analysis, depending on the built-in `LoopInfo`
+struct LoopMetrics {
+    num_loops: usize,
+    max_depth: usize,
+}
+
+static LOOP_METRICS_COMPUTATIONS: std::sync::atomic::AtomicUsize =
+    std::sync::atomic::AtomicUsize::new(0);
+
+impl FunctionAnalysisPass for LoopMetrics {
+    fn compute(analysis: &FunctionAnalysis) -> Self {
+        LOOP_METRICS_COMPUTATIONS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let loop_info = analysis.loop_info();
+        Self {
+            num_loops: loop_info.loops().count(),
+            max_depth: loop_info.loops().map(|l| l.depth()).max().unwrap_or(0),
+        }
+    }
+}
+
+/// A custom per-function analysis, depending on another custom analysis
+struct HasNestedLoops(bool);
+
+impl FunctionAnalysisPass for HasNestedLoops {
+    fn compute(analysis: &FunctionAnalysis) -> Self {
+        Self(analysis.analysis::<LoopMetrics>().max_depth > 1)
+    }
+}
+
+/// A custom module analysis, depending on custom per-function analyses
+struct FunctionsWithLoops(Vec<String>);
+
+impl ModuleAnalysisPass for FunctionsWithLoops {
+    fn compute(analysis: &ModuleAnalysis) -> Self {
+        Self(
+            analysis
+                .module()
+                .functions
+                .iter()
+                .filter(|f| {
+                    analysis
+                        .fn_analysis(&f.name)
+                        .analysis::<LoopMetrics>()
+                        .num_loops
+                        > 0
+                })
+                .map(|f| f.name.clone())
+                .sorted()
+                .collect(),
+        )
+    }
+}
+
+#[test]
+fn custom_analyses() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    let fn_analysis = analysis.fn_analysis("nested_loop");
+    assert_eq!(fn_analysis.function().name, "nested_loop");
+    assert!(fn_analysis.analysis::<HasNestedLoops>().0);
+    let metrics = fn_analysis.analysis::<LoopMetrics>();
+    assert_eq!(metrics.num_loops, 2);
+    assert_eq!(metrics.max_depth, 2);
+
+    let fn_analysis = analysis.fn_analysis("while_loop");
+    assert!(!fn_analysis.analysis::<HasNestedLoops>().0);
+    assert_eq!(fn_analysis.analysis::<LoopMetrics>().num_loops, 1);
+
+    let with_loops = analysis.analysis::<FunctionsWithLoops>();
+    assert!(with_loops.0.iter().any(|name| name == "nested_loop"));
+    assert!(with_loops.0.iter().any(|name| name == "while_loop"));
+
+    // each function's `LoopMetrics` was computed only once, even though it was
+    // requested several times
+    let computations = LOOP_METRICS_COMPUTATIONS.load(std::sync::atomic::Ordering::SeqCst);
+    assert_eq!(computations, module.functions.len());
+    analysis.analysis::<FunctionsWithLoops>();
+    analysis
+        .fn_analysis("nested_loop")
+        .analysis::<LoopMetrics>();
+    assert_eq!(
+        LOOP_METRICS_COMPUTATIONS.load(std::sync::atomic::Ordering::SeqCst),
+        computations
+    );
+}
+
+/// A custom per-function analysis, independent of the others so that we can
+/// count its computations separately
+struct NumBlocks(usize);
+
+static NUM_BLOCKS_COMPUTATIONS: std::sync::atomic::AtomicUsize =
+    std::sync::atomic::AtomicUsize::new(0);
+
+impl FunctionAnalysisPass for NumBlocks {
+    fn compute(analysis: &FunctionAnalysis) -> Self {
+        NUM_BLOCKS_COMPUTATIONS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Self(analysis.function().basic_blocks.len())
+    }
+}
+
+/// A custom module analysis, depending on `NumBlocks`
+struct TotalBlocks(usize);
+
+static TOTAL_BLOCKS_COMPUTATIONS: std::sync::atomic::AtomicUsize =
+    std::sync::atomic::AtomicUsize::new(0);
+
+impl ModuleAnalysisPass for TotalBlocks {
+    fn compute(analysis: &ModuleAnalysis) -> Self {
+        TOTAL_BLOCKS_COMPUTATIONS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Self(
+            analysis
+                .functions()
+                .map(|f| analysis.fn_analysis(&f.name).analysis::<NumBlocks>().0)
+                .sum(),
+        )
+    }
+}
+
+#[test]
+fn invalidation() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let mut analysis = ModuleAnalysis::new(&module);
+    let num_blocks = || NUM_BLOCKS_COMPUTATIONS.load(std::sync::atomic::Ordering::SeqCst);
+    let total_blocks = || TOTAL_BLOCKS_COMPUTATIONS.load(std::sync::atomic::Ordering::SeqCst);
+
+    let total = analysis.analysis::<TotalBlocks>().0;
+    assert_eq!(
+        total,
+        module
+            .functions
+            .iter()
+            .map(|f| f.basic_blocks.len())
+            .sum::<usize>()
+    );
+    assert_eq!(num_blocks(), module.functions.len());
+    assert_eq!(total_blocks(), 1);
+    analysis.fn_analysis("nested_loop").control_flow_graph();
+    analysis.fn_analysis("while_loop").control_flow_graph();
+
+    // invalidating a function recomputes its analyses, and the module-wide
+    // analyses which may depend on it, but not the analyses of other functions
+    analysis
+        .invalidate_function("nested_loop")
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert!(!analysis
+        .fn_analysis("nested_loop")
+        .is_computed(AnalysisKind::ControlFlowGraph));
+    assert!(analysis
+        .fn_analysis("while_loop")
+        .is_computed(AnalysisKind::ControlFlowGraph));
+    assert_eq!(analysis.analysis::<TotalBlocks>().0, total);
+    assert_eq!(num_blocks(), module.functions.len() + 1);
+    assert_eq!(total_blocks(), 2);
+
+    assert_eq!(
+        analysis.invalidate_function("nonexistent"),
+        Err(Error::FunctionNotFound {
+            name: "nonexistent".into()
+        })
+    );
+
+    // invalidating the module-wide analyses keeps the analyses of functions
+    analysis.invalidate_module_analyses();
+    assert_eq!(analysis.analysis::<TotalBlocks>().0, total);
+    assert_eq!(num_blocks(), module.functions.len() + 1);
+    assert_eq!(total_blocks(), 3);
+
+    // invalidating everything recomputes every function's analyses
+    analysis.invalidate_all();
+    assert!(!analysis
+        .fn_analysis("while_loop")
+        .is_computed(AnalysisKind::ControlFlowGraph));
+    assert_eq!(analysis.analysis::<TotalBlocks>().0, total);
+    assert_eq!(num_blocks(), 2 * module.functions.len() + 1);
+    assert_eq!(total_blocks(), 4);
+}
+
+/// The dominators of each block, computed as a forward dataflow problem.
+/// `None` represents the set of all blocks.
+struct Dominators;
+
+impl<'m> DataflowAnalysis<'m> for Dominators {
+    type Fact = Option<BTreeSet<&'m Name>>;
+    const DIRECTION: DataflowDirection = DataflowDirection::Forward;
+
+    fn bottom(&self) -> Self::Fact {
+        None
+    }
+
+    fn boundary(&self) -> Self::Fact {
+        Some(BTreeSet::new())
+    }
+
+    fn join(&self, fact: &mut Self::Fact, other: &Self::Fact) {
+        match (fact.as_mut(), other) {
+            (_, None) => {}
+            (None, Some(_)) => *fact = other.clone(),
+            (Some(fact), Some(other)) => fact.retain(|block| other.contains(block)),
+        }
+    }
+
+    fn transfer_block(&self, block: &'m BasicBlock, fact: &mut Self::Fact) {
+        if let Some(fact) = fact {
+            fact.insert(&block.name);
+        }
+    }
+}
+
+/// The results of all the instructions which may execute at or after each
+/// point, computed as a backward dataflow problem
+struct LaterResults;
+
+impl<'m> DataflowAnalysis<'m> for LaterResults {
+    type Fact = BTreeSet<&'m Name>;
+    const DIRECTION: DataflowDirection = DataflowDirection::Backward;
+
+    fn bottom(&self) -> Self::Fact {
+        BTreeSet::new()
+    }
+
+    fn join(&self, fact: &mut Self::Fact, other: &Self::Fact) {
+        fact.extend(other.iter().copied());
+    }
+
+    fn transfer_instruction(&self, inst: &'m Instruction, fact: &mut Self::Fact) {
+        if let Some(name) = inst.try_get_result() {
+            fact.insert(name);
+        }
+    }
+}
+
+#[test]
+fn dataflow() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+
+    for func in &module.functions {
+        let fn_analysis = analysis.fn_analysis(&func.name);
+        let cfg = fn_analysis.control_flow_graph();
+        let domtree = fn_analysis.dominator_tree();
+
+        // the dataflow dominators agree with the `DominatorTree`
+        let dominators = cfg.solve_dataflow(&Dominators);
+        for block in cfg.reachable_blocks() {
+            let mut expected = BTreeSet::new();
+            let mut dominator = Some(block);
+            while let Some(d) = dominator {
+                expected.insert(d);
+                dominator = domtree.idom(d);
+            }
+            assert_eq!(
+                dominators.fact_out(block),
+                Some(&Some(expected)),
+                "dominators of {} in {}",
+                block,
+                func.name
+            );
+        }
+
+        // each block is followed by the results of all the blocks it can reach
+        let later_results = cfg.solve_dataflow(&LaterResults);
+        for bb in &func.basic_blocks {
+            let mut expected = BTreeSet::new();
+            let mut reached: HashSet<&Name> = HashSet::new();
+            let mut worklist = vec![&bb.name];
+            while let Some(block) = worklist.pop() {
+                if reached.insert(block) {
+                    let bb = func.get_bb_by_name(block).unwrap();
+                    expected.extend(bb.instrs.iter().filter_map(|i| i.try_get_result()));
+                    worklist.extend(cfg.succs(block).filter_map(|succ| match succ {
+                        CFGNode::Block(succ) => Some(succ),
+                        _ => None,
+                    }));
+                }
+            }
+            assert_eq!(later_results.fact_in(&bb.name), Some(&expected));
+        }
+        if let Some(fact) = later_results.fact_at_return() {
+            assert!(fact.is_empty());
+        }
+        assert_eq!(later_results.fact_at_unwind(), None);
+    }
+
+    // `infinite_loop` never returns
+    let cfg = analysis.fn_analysis("infinite_loop").control_flow_graph();
+    assert_eq!(cfg.solve_dataflow(&Dominators).fact_at_return(), None);
+    let cfg = analysis.fn_analysis("while_loop").control_flow_graph();
+    assert!(cfg.solve_dataflow(&Dominators).fact_at_return().is_some());
+}
+
+#[test]
+fn for_loop_liveness() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let liveness = analysis.fn_analysis("for_loop").liveness();
+
+    // 1:
+    //   %2 = alloca ; %3 = bitcast %2 ; lifetime.start(%3) ; store 0, %2
+    //   %4 = icmp sgt %0, 0 ; %5 = load %2 ; br %4, %9, %6
+    // 6:
+    //   %7 = phi [%5, %1], [%14, %9] ; %8 = add %7, -3
+    //   lifetime.end(%3) ; ret %8
+    // 9:
+    //   %10 = phi [%14, %9], [%5, %1] ; %11 = phi [%13, %9], [0, %1]
+    //   %12 = add %10, 1 ; store %12, %2 ; %13 = add %11, 1
+    //   %14 = load %2 ; %15 = icmp eq %13, %0 ; br %15, %6, %9
+
+    let bb1_name = Name::from(1);
+    let bb6_name = Name::from(6);
+    let bb9_name = Name::from(9);
+    let names = |nums: &[usize]| -> Vec<Name> { nums.iter().map(|&n| Name::from(n)).collect() };
+
+    let live_in: Vec<&Name> = liveness.live_in(&bb1_name).sorted().collect();
+    assert_eq!(live_in, names(&[0]).iter().collect::<Vec<_>>());
+    let live_out: Vec<&Name> = liveness.live_out(&bb1_name).sorted().collect();
+    assert_eq!(live_out, names(&[0, 2, 3, 5]).iter().collect::<Vec<_>>());
+
+    // the phi result %7 isn't live-in to 6, and neither are its operands
+    let live_in: Vec<&Name> = liveness.live_in(&bb6_name).sorted().collect();
+    assert_eq!(live_in, names(&[3]).iter().collect::<Vec<_>>());
+    assert_eq!(liveness.live_out(&bb6_name).count(), 0);
+
+    // %13 and %14 are live-out of 9, as they are used by the phis on the back
+    // edge (and %14 on the exit edge)
+    let live_in: Vec<&Name> = liveness.live_in(&bb9_name).sorted().collect();
+    assert_eq!(live_in, names(&[0, 2, 3]).iter().collect::<Vec<_>>());
+    let live_out: Vec<&Name> = liveness.live_out(&bb9_name).sorted().collect();
+    assert_eq!(
+        live_out,
+        names(&[0, 2, 3, 13, 14]).iter().collect::<Vec<_>>()
+    );
+    assert!(liveness.is_live_out(&Name::from(14), &bb9_name));
+    assert!(!liveness.is_live_in(&Name::from(14), &bb9_name));
+    assert!(!liveness.is_live_in(&Name::from(5), &bb6_name));
+
+    // %4 is live from its definition until the branch in 1
+    assert!(!liveness.is_live_after(&Name::from(4), &bb1_name, 3));
+    assert!(liveness.is_live_after(&Name::from(4), &bb1_name, 4));
+    assert!(liveness.is_live_after(&Name::from(4), &bb1_name, 5));
+    assert!(!liveness.is_live_after(&Name::from(4), &bb1_name, 6));
+    // %7 is live only between its definition and its use in 6
+    assert!(liveness.is_live_after(&Name::from(7), &bb6_name, 0));
+    assert!(!liveness.is_live_after(&Name::from(7), &bb6_name, 1));
+    // after the terminator is the same as live-out
+    let live_after: Vec<&Name> = liveness
+        .live_after(&bb9_name, 7)
+        .unwrap()
+        .into_iter()
+        .sorted()
+        .collect();
+    let live_out: Vec<&Name> = liveness.live_out(&bb9_name).sorted().collect();
+    assert_eq!(live_after, live_out);
+    // before the load of %14, %13 is live but %14 isn't yet
+    let live_after: Vec<&Name> = liveness
+        .live_after(&bb9_name, 4)
+        .unwrap()
+        .into_iter()
+        .sorted()
+        .collect();
+    assert_eq!(live_after, names(&[0, 2, 3, 13]).iter().collect::<Vec<_>>());
+    // out of range
+    assert_eq!(liveness.live_after(&bb9_name, 8), None);
+    assert_eq!(liveness.live_after(&Name::from(100), 0), None);
+    assert!(!liveness.is_live_after(&Name::from(13), &bb9_name, 8));
+    assert_eq!(liveness.live_sets(&Name::from(100)), None);
+
+    // `live_sets()` gives the same sets as `live_after()` for each instruction
+    for bb in &module.get_func_by_name("for_loop").unwrap().basic_blocks {
+        let live_sets = liveness.live_sets(&bb.name).unwrap();
+        assert_eq!(live_sets.len(), bb.instrs.len() + 1);
+        for (index, live) in live_sets.into_iter().enumerate() {
+            assert_eq!(Some(live), liveness.live_after(&bb.name, index));
+        }
+    }
+}
+
+#[test]
+fn unreachable_liveness() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let func = module
+        .get_func_by_name("for_loop")
+        .expect("Failed to find function");
+
+    // add an unreachable copy of the end of bb6 to `for_loop`, which uses %3
+    // and %8
+    let mut edited = func.clone();
+    let dead_name = Name::from("dead");
+    let bb6 = edited.basic_blocks[1].clone();
+    let mut dead = BasicBlock::new(dead_name.clone());
+    dead.instrs.push(bb6.instrs[2].clone());
+    dead.term = bb6.term;
+    edited.basic_blocks.push(dead);
+
+    let fn_analysis = FunctionAnalysis::new(&edited);
+    let liveness = fn_analysis.liveness();
+    assert_eq!(liveness.live_in(&dead_name).count(), 0);
+    assert_eq!(liveness.live_out(&dead_name).count(), 0);
+    assert_eq!(liveness.live_after(&dead_name, 0), Some(HashSet::new()));
+    assert_eq!(liveness.live_after(&dead_name, 1), Some(HashSet::new()));
+    assert!(!liveness.is_live_after(&Name::from(8), &dead_name, 0));
+    assert_eq!(
+        liveness.live_sets(&dead_name),
+        Some(vec![HashSet::new(), HashSet::new()])
+    );
+    assert_eq!(liveness.live_after(&dead_name, 2), None);
+}
+
+#[test]
+fn for_loop_def_use() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("for_loop");
+    let def_use = fn_analysis.def_use_info();
+
+    // see the listing of `for_loop` in `for_loop_liveness()`
+    let bb1_name = Name::from(1);
+    let bb6_name = Name::from(6);
+    let bb9_name = Name::from(9);
+    let loc = |block, index| InstructionLocation { block, index };
+
+    assert_eq!(
+        def_use.definition(&Name::from(0)),
+        Some(Definition::Parameter(0))
+    );
+    assert_eq!(
+        def_use.definition(&Name::from(7)),
+        Some(Definition::Instruction(loc(&bb6_name, 0)))
+    );
+    assert_eq!(
+        def_use.definition(&Name::from(15)),
+        Some(Definition::Instruction(loc(&bb9_name, 6)))
+    );
+    assert_eq!(def_use.definition(&Name::from(100)), None);
+    assert_eq!(def_use.definitions().count(), 13);
+
+    // the parameter is used by both comparisons
+    let uses: Vec<Use> = def_use.uses(&Name::from(0)).sorted().collect();
+    assert_eq!(
+        uses,
+        vec![
+            Use {
+                location: loc(&bb1_name, 4),
+                phi_incoming_block: None
+            },
+            Use {
+                location: loc(&bb9_name, 6),
+                phi_incoming_block: None
+            },
+        ]
+    );
+
+    // %14 is used by a phi in each successor of 9, coming from 9
+    let uses: Vec<Use> = def_use.uses(&Name::from(14)).sorted().collect();
+    assert_eq!(
+        uses,
+        vec![
+            Use {
+                location: loc(&bb6_name, 0),
+                phi_incoming_block: Some(&bb9_name)
+            },
+            Use {
+                location: loc(&bb9_name, 0),
+                phi_incoming_block: Some(&bb9_name)
+            },
+        ]
+    );
+
+    // %8 is used by the `ret` terminator
+    let uses: Vec<Use> = def_use.uses(&Name::from(8)).collect();
+    assert_eq!(
+        uses,
+        vec![Use {
+            location: loc(&bb6_name, 3),
+            phi_incoming_block: None
+        }]
+    );
+    let ret = loc(&bb6_name, 3).get(fn_analysis.function());
+    assert!(matches!(ret, Some(term) if term.is_right()));
+    assert!(def_use.is_used(&Name::from(15)));
+    assert!(!def_use.is_used(&Name::from(100)));
+
+    // use-def: the `store` in 9 uses %12 and %2
+    let used: Vec<&Name> = def_use.used_values(loc(&bb9_name, 3)).collect();
+    assert_eq!(used, vec![&Name::from(2), &Name::from(12)]);
+    let used: Vec<&Name> = def_use.used_values(loc(&bb6_name, 0)).collect();
+    assert_eq!(used, vec![&Name::from(5), &Name::from(14)]);
+}
+
+#[test]
+fn for_loop_dependence_graphs() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("for_loop");
+
+    // see the listing of `for_loop` in `for_loop_liveness()`
+    let bb1_name = Name::from(1);
+    let bb6_name = Name::from(6);
+    let bb9_name = Name::from(9);
+    let loc = |block, index| InstructionLocation { block, index };
+    let name2 = Name::from(2);
+    let name3 = Name::from(3);
+    let name4 = Name::from(4);
+
+    let ddg = fn_analysis.data_dependence_graph();
+
+    // the load of %5 depends on the alloca, and on the writes before it
+    let deps: Vec<(InstructionLocation, DependenceKind)> = ddg
+        .get_data_dependencies(loc(&bb1_name, 5))
+        .sorted()
+        .collect();
+    assert_eq!(
+        deps,
+        vec![
+            (loc(&bb1_name, 0), DependenceKind::Data(&name2)),
+            (loc(&bb1_name, 2), DependenceKind::Memory),
+            (loc(&bb1_name, 3), DependenceKind::Memory),
+        ]
+    );
+
+    // the load of %14 also depends on the store in the loop, and on the
+    // volatile loads (including itself, on the previous iteration), which
+    // may write memory
+    let deps: Vec<(InstructionLocation, DependenceKind)> = ddg
+        .get_data_dependencies(loc(&bb9_name, 5))
+        .sorted()
+        .collect();
+    assert_eq!(
+        deps,
+        vec![
+            (loc(&bb1_name, 0), DependenceKind::Data(&name2)),
+            (loc(&bb1_name, 2), DependenceKind::Memory),
+            (loc(&bb1_name, 3), DependenceKind::Memory),
+            (loc(&bb1_name, 5), DependenceKind::Memory),
+            (loc(&bb9_name, 3), DependenceKind::Memory),
+            (loc(&bb9_name, 5), DependenceKind::Memory),
+        ]
+    );
+
+    // the only dependent of the comparison %4 is the branch
+    let dependents: Vec<(InstructionLocation, DependenceKind)> =
+        ddg.get_data_dependents(loc(&bb1_name, 4)).collect();
+    assert_eq!(
+        dependents,
+        vec![(loc(&bb1_name, 6), DependenceKind::Data(&name4))]
+    );
+
+    let pdg = fn_analysis.program_dependence_graph();
+
+    // everything in the loop body is control-dependent on the branches in 1
+    // and 9. Nothing in 6 is control-dependent on anything, except that the
+    // phi depends on the branches deciding which predecessor it came from
+    let deps: Vec<(InstructionLocation, DependenceKind)> = pdg
+        .get_imm_dependencies(loc(&bb9_name, 4))
+        .filter(|(_, kind)| *kind == DependenceKind::Control)
+        .sorted()
+        .collect();
+    assert_eq!(
+        deps,
+        vec![
+            (loc(&bb1_name, 6), DependenceKind::Control),
+            (loc(&bb9_name, 7), DependenceKind::Control),
+        ]
+    );
+    let deps: Vec<(InstructionLocation, DependenceKind)> = pdg
+        .get_imm_dependencies(loc(&bb6_name, 0))
+        .filter(|(_, kind)| *kind == DependenceKind::Control)
+        .sorted()
+        .collect();
+    assert_eq!(
+        deps,
+        vec![
+            (loc(&bb1_name, 6), DependenceKind::Control),
+            (loc(&bb9_name, 7), DependenceKind::Control),
+        ]
+    );
+    for index in 1..=3 {
+        assert!(pdg
+            .get_imm_dependencies(loc(&bb6_name, index))
+            .all(|(_, kind)| kind != DependenceKind::Control));
+    }
+    let deps: Vec<(InstructionLocation, DependenceKind)> = pdg
+        .get_imm_dependencies(loc(&bb6_name, 2))
+        .sorted()
+        .collect();
+    assert_eq!(
+        deps,
+        vec![
+            (loc(&bb1_name, 1), DependenceKind::Data(&name3)),
+            (loc(&bb1_name, 2), DependenceKind::Memory),
+            (loc(&bb1_name, 3), DependenceKind::Memory),
+            (loc(&bb1_name, 5), DependenceKind::Memory),
+            (loc(&bb9_name, 3), DependenceKind::Memory),
+            (loc(&bb9_name, 5), DependenceKind::Memory),
+        ]
+    );
+    assert_eq!(pdg.get_imm_dependents(loc(&bb1_name, 6)).count(), 9);
+
+    // the return value depends on the comparison in 1, through the phi
+    let chain = pdg
+        .dependence_chain(loc(&bb6_name, 3), loc(&bb1_name, 4))
+        .expect("return value should depend on the comparison");
+    assert_eq!(
+        chain,
+        vec![
+            loc(&bb6_name, 3),
+            loc(&bb6_name, 1),
+            loc(&bb6_name, 0),
+            loc(&bb1_name, 6),
+            loc(&bb1_name, 4),
+        ]
+    );
+    assert_eq!(
+        pdg.dependence_kinds(loc(&bb9_name, 5), loc(&bb1_name, 6)),
+        &[DependenceKind::Control]
+    );
+    assert_eq!(
+        pdg.dependence_kinds(loc(&bb1_name, 4), loc(&bb1_name, 6)),
+        &[]
+    );
+    assert_eq!(
+        pdg.dependence_chain(loc(&bb1_name, 4), loc(&bb6_name, 3)),
+        None
+    );
+    assert_eq!(
+        pdg.dependence_chain(loc(&bb1_name, 4), loc(&bb1_name, 4)),
+        Some(vec![loc(&bb1_name, 4)])
+    );
+
+    let options = DotOptions {
+        edge_kinds: true,
+        highlight_instructions: std::iter::once(loc(&bb1_name, 4)).collect(),
+        ..DotOptions::default()
+    };
+    let dot = pdg.to_dot(&options);
+    assert!(dot.contains("\"%1[4]\" [label=\"%1[4]\", style=filled, fillcolor=yellow];"));
+    assert!(dot.contains("\"%9[5]\" -> \"%1[6]\" [label=\"control\"];"));
+    assert!(dot.contains("\"%1[6]\" -> \"%1[4]\" [label=\"%4\"];"));
+}
+
+#[test]
+fn for_loop_ssa_dependence_graphs() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("for_loop");
+    let func = fn_analysis.function();
+
+    // see the listing of `for_loop` in `for_loop_liveness()`
+    let bb1_name = Name::from(1);
+    let bb6_name = Name::from(6);
+    let loc = |block, index| InstructionLocation { block, index };
+    let name2 = Name::from(2);
+    let name3 = Name::from(3);
+
+    let ssa_only = DependenceOptions::default().with_memory(false);
+    let ddg = fn_analysis.data_dependence_graph_with(ExceptionalFlow::MergeWithReturn, ssa_only);
+    let pdg = fn_analysis.program_dependence_graph_with(ExceptionalFlow::MergeWithReturn, ssa_only);
+
+    // without memory dependences, the loads depend only on the alloca
+    let deps: Vec<(InstructionLocation, DependenceKind)> =
+        ddg.get_data_dependencies(loc(&bb1_name, 5)).collect();
+    assert_eq!(
+        deps,
+        vec![(loc(&bb1_name, 0), DependenceKind::Data(&name2))]
+    );
+    let deps: Vec<(InstructionLocation, DependenceKind)> =
+        pdg.get_imm_dependencies(loc(&bb6_name, 2)).collect();
+    assert_eq!(
+        deps,
+        vec![(loc(&bb1_name, 1), DependenceKind::Data(&name3))]
+    );
+
+    for bb in &func.basic_blocks {
+        for index in 0..=bb.instrs.len() {
+            let location = loc(&bb.name, index);
+            assert!(ddg
+                .get_data_dependencies(location)
+                .all(|(_, kind)| kind != DependenceKind::Memory));
+            assert!(pdg
+                .get_imm_dependencies(location)
+                .all(|(_, kind)| kind != DependenceKind::Memory));
+        }
+    }
+
+    // the graphs with memory dependences are cached separately
+    assert!(fn_analysis
+        .data_dependence_graph()
+        .get_data_dependencies(loc(&bb1_name, 5))
+        .any(|(_, kind)| kind == DependenceKind::Memory));
+    assert!(fn_analysis
+        .program_dependence_graph()
+        .get_imm_dependencies(loc(&bb6_name, 2))
+        .any(|(_, kind)| kind == DependenceKind::Memory));
+}
+
+#[test]
+fn for_loop_slicing() {
+    init_logging();
+    let module = Module::from_bc_path(LOOP_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let pdg = analysis.fn_analysis("for_loop").program_dependence_graph();
+
+    // see the listing of `for_loop` in `for_loop_liveness()`
+    let bb1_name = Name::from(1);
+    let bb6_name = Name::from(6);
+    let bb9_name = Name::from(9);
+    let loc = |block, index| InstructionLocation { block, index };
+    let all_of = |block, num_instrs| (0..=num_instrs).map(move |index| loc(block, index));
+
+    // the return value depends on everything except `lifetime.end`
+    let ret = loc(&bb6_name, 3);
+    let slice = pdg.backward_slice(ret);
+    assert_eq!(slice.len(), 18);
+    assert!(slice.contains(ret));
+    assert!(!slice.contains(loc(&bb6_name, 2)));
+    let blocks: Vec<&Name> = slice.blocks().collect();
+    assert_eq!(blocks, vec![&bb1_name, &bb6_name, &bb9_name]);
+
+    // the loop condition in 1 affects the loop, and everything after it
+    let cond = loc(&bb1_name, 4);
+    let slice = pdg.forward_slice(cond);
+    let instructions: Vec<InstructionLocation> = slice.instructions().collect();
+    let expected: Vec<InstructionLocation> = vec![cond, loc(&bb1_name, 6)]
+        .into_iter()
+        .chain(all_of(&bb6_name, 3))
+        .chain(all_of(&bb9_name, 7))
+        .collect();
+    assert_eq!(instructions, expected);
+
+    // the chop between them is the forward slice, except `lifetime.end`
+    let chop = pdg.chop(cond, ret);
+    let instructions: Vec<InstructionLocation> = chop.instructions().collect();
+    let expected: Vec<InstructionLocation> = expected
+        .into_iter()
+        .filter(|location| *location != loc(&bb6_name, 2))
+        .collect();
+    assert_eq!(instructions, expected);
+
+    // `lifetime.end` doesn't affect the return value
+    assert!(pdg.chop(loc(&bb6_name, 2), ret).is_empty());
+    assert!(pdg.backward_slice(loc(&bb1_name, 50)).is_empty());
+}
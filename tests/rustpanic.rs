@@ -10,7 +10,7 @@ fn init_logging() {
 /// panic.c and panic.bc are taken from [`haybale`]'s test suite
 ///
 /// [`haybale`]: https://crates.io/crates/haybale
-const PANIC_BC_PATH: &'static str = "tests/bcfiles/panic.bc";
+const PANIC_BC_PATH: &str = "tests/bcfiles/panic.bc";
 
 #[test]
 fn begin_panic_cfg() {
@@ -117,6 +117,84 @@ fn begin_panic_cfg() {
     assert_eq!(return_preds, vec![&bb1_name]);
 }
 
+#[test]
+fn begin_panic_cfg_edge_kinds() {
+    init_logging();
+    let module = Module::from_bc_path(PANIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let cfg = analysis
+        .fn_analysis("_ZN3std9panicking11begin_panic17h5ae0871c3ba84f98E")
+        .control_flow_graph();
+
+    let bbstart_name = Name::from("start");
+    let bb1_name = Name::from("bb1");
+    let bb1_node = CFGNode::Block(&bb1_name);
+    let bb2_name = Name::from("bb2");
+    let bb2_node = CFGNode::Block(&bb2_name);
+    let bb4_name = Name::from("bb4");
+    let bb5_name = Name::from("bb5");
+    let bb5_node = CFGNode::Block(&bb5_name);
+    let bb6_name = Name::from("bb6");
+    let bbcleanup_name = Name::from("cleanup");
+    let bbcleanup_node = CFGNode::Block(&bbcleanup_name);
+    let bbcleanup1_name = Name::from("cleanup1");
+    let bbunreachable_name = Name::from("unreachable");
+    let bbunreachable_node = CFGNode::Block(&bbunreachable_name);
+
+    let bbstart_succs: Vec<(CFGNode, CFGEdgeKind)> = cfg
+        .succs_with_kind(&bbstart_name)
+        .sorted_by_key(|(n, _)| *n)
+        .collect();
+    assert_eq!(
+        bbstart_succs,
+        vec![
+            (bb2_node, CFGEdgeKind::InvokeNormal),
+            (bbcleanup_node, CFGEdgeKind::InvokeUnwind)
+        ]
+    );
+
+    let bb4_succs: Vec<(CFGNode, CFGEdgeKind)> = cfg
+        .succs_with_kind(&bb4_name)
+        .sorted_by_key(|(n, _)| *n)
+        .collect();
+    let bbcleanup1_node = CFGNode::Block(&bbcleanup1_name);
+    assert_eq!(
+        bb4_succs,
+        vec![
+            (bbcleanup1_node, CFGEdgeKind::InvokeUnwind),
+            (bbunreachable_node, CFGEdgeKind::InvokeNormal)
+        ]
+    );
+
+    let bbcleanup1_preds: Vec<(&Name, CFGEdgeKind)> = cfg
+        .preds_with_kind(&bbcleanup1_name)
+        .sorted_by_key(|(n, _)| *n)
+        .collect();
+    assert_eq!(
+        bbcleanup1_preds,
+        vec![
+            (&bb2_name, CFGEdgeKind::InvokeUnwind),
+            (&bb4_name, CFGEdgeKind::InvokeUnwind)
+        ]
+    );
+
+    let bb6_succs: Vec<(CFGNode, CFGEdgeKind)> = cfg
+        .succs_with_kind(&bb6_name)
+        .sorted_by_key(|(n, _)| *n)
+        .collect();
+    assert_eq!(
+        bb6_succs,
+        vec![
+            (bb1_node, CFGEdgeKind::BranchFalse),
+            (bb5_node, CFGEdgeKind::BranchTrue)
+        ]
+    );
+
+    let return_preds: Vec<(&Name, CFGEdgeKind)> = cfg.preds_of_return_with_kind().collect();
+    assert_eq!(return_preds, vec![(&bb1_name, CFGEdgeKind::Resume)]);
+}
+
 #[test]
 fn begin_panic_domtree() {
     init_logging();
@@ -294,3 +372,160 @@ fn begin_panic_cdg() {
         0
     );
 }
+
+#[test]
+fn begin_panic_separate_unwind() {
+    init_logging();
+    let module = Module::from_bc_path(PANIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("_ZN3std9panicking11begin_panic17h5ae0871c3ba84f98E");
+
+    // Same CFG as above, except that bb1 ends in `resume`, so it goes to
+    // (unwind) rather than (ret)
+
+    let bb1_name = Name::from("bb1");
+
+    let cfg = fn_analysis.control_flow_graph_with(ExceptionalFlow::SeparateUnwind);
+    let bb1_succs: Vec<CFGNode> = cfg.succs(&bb1_name).collect();
+    assert_eq!(bb1_succs, vec![CFGNode::Unwind]);
+    let unwind_preds: Vec<&Name> = cfg.preds_of_unwind().collect();
+    assert_eq!(unwind_preds, vec![&Name::from("bb1")]);
+    assert_eq!(cfg.preds_of_return().count(), 0);
+
+    let domtree = fn_analysis.dominator_tree_with(ExceptionalFlow::SeparateUnwind);
+    assert_eq!(domtree.idom(&Name::from("bb1")), Some(&Name::from("bb6")));
+    assert_eq!(domtree.idom_of_unwind(), Some(&Name::from("bb1")));
+    assert_eq!(domtree.idom_of_return(), None);
+
+    let postdomtree = fn_analysis.postdominator_tree_with(ExceptionalFlow::SeparateUnwind);
+    assert_eq!(
+        postdomtree.ipostdom(&Name::from("bb1")),
+        Some(CFGNode::Unwind)
+    );
+    assert_eq!(
+        postdomtree.ipostdom(&Name::from("bb6")),
+        Some(CFGNode::Block(&Name::from("bb1")))
+    );
+    let unwind_children: Vec<&Name> = postdomtree.children_of_unwind().collect();
+    assert_eq!(unwind_children, vec![&Name::from("bb1")]);
+    assert_eq!(postdomtree.children_of_return().count(), 0);
+    assert!(postdomtree.postdominates(CFGNode::Return, CFGNode::Unwind));
+
+    // the control dependencies are the same as with the merged CFG
+    let cdg = fn_analysis.control_dependence_graph_with(ExceptionalFlow::SeparateUnwind);
+    assert_eq!(
+        cdg.get_imm_control_dependencies(&Name::from("bb5"))
+            .collect::<Vec<_>>(),
+        vec![&Name::from("bb6")]
+    );
+    assert_eq!(
+        cdg.get_imm_control_dependencies(&Name::from("cleanup"))
+            .collect::<Vec<_>>(),
+        vec![&Name::from("start")]
+    );
+    assert_eq!(
+        cdg.get_imm_control_dependencies(&Name::from("bb1")).count(),
+        0
+    );
+}
+
+#[test]
+fn begin_panic_omit_exceptional_flow() {
+    init_logging();
+    let module = Module::from_bc_path(PANIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let fn_analysis = analysis.fn_analysis("_ZN3std9panicking11begin_panic17h5ae0871c3ba84f98E");
+
+    // CFG without exceptional edges:
+    //         start
+    //           |
+    //          bb2
+    //           |
+    //          bb4
+    //           |
+    //      unreachable
+    //           |
+    //     (unreachable)
+    //
+    // All the other blocks are only reachable by unwinding.
+
+    let bbstart_name = Name::from("start");
+    let bb4_name = Name::from("bb4");
+
+    let cfg = fn_analysis.control_flow_graph_with(ExceptionalFlow::Omit);
+    let bbstart_succs: Vec<CFGNode> = cfg.succs(&bbstart_name).collect();
+    assert_eq!(bbstart_succs, vec![CFGNode::Block(&Name::from("bb2"))]);
+    let bb4_succs: Vec<CFGNode> = cfg.succs(&bb4_name).collect();
+    assert_eq!(bb4_succs, vec![CFGNode::Block(&Name::from("unreachable"))]);
+    assert_eq!(cfg.preds(&Name::from("cleanup")).count(), 0);
+    assert_eq!(cfg.preds(&Name::from("cleanup1")).count(), 0);
+    assert_eq!(cfg.succs(&Name::from("bb1")).count(), 0);
+    assert_eq!(cfg.preds_of_return().count(), 0);
+
+    let reachable: Vec<&Name> = cfg.reachable_blocks().collect();
+    assert_eq!(
+        reachable,
+        vec![
+            &bbstart_name,
+            &Name::from("bb2"),
+            &bb4_name,
+            &Name::from("unreachable"),
+        ]
+    );
+    let unreachable: Vec<&Name> = cfg.unreachable_blocks().collect();
+    assert_eq!(
+        unreachable.len(),
+        fn_analysis.function().basic_blocks.len() - reachable.len()
+    );
+    assert!(unreachable.contains(&&Name::from("cleanup")));
+    assert!(unreachable.contains(&&Name::from("bb6")));
+    assert_eq!(cfg.rpo_number(&Name::from("cleanup")), None);
+    assert_eq!(cfg.rpo_number(&bb4_name), Some(2));
+
+    let domtree = fn_analysis.dominator_tree_with(ExceptionalFlow::Omit);
+    assert_eq!(domtree.idom(&Name::from("bb2")), Some(&Name::from("start")));
+    assert_eq!(domtree.idom(&Name::from("bb4")), Some(&Name::from("bb2")));
+    assert_eq!(
+        domtree.idom(&Name::from("unreachable")),
+        Some(&Name::from("bb4"))
+    );
+    assert_eq!(domtree.idom(&Name::from("cleanup")), None);
+    assert_eq!(domtree.idom(&Name::from("bb6")), None);
+    assert_eq!(domtree.idom_of_return(), None);
+
+    // the function never returns normally, so nothing postdominates anything
+    let postdomtree = fn_analysis.postdominator_tree_with(ExceptionalFlow::Omit);
+    for block in &["start", "bb1", "bb2", "bb4", "bb6", "unreachable"] {
+        assert_eq!(postdomtree.ipostdom(&Name::from(*block)), None);
+    }
+}
+
+#[test]
+fn begin_panic_call_sites() {
+    init_logging();
+    let module = Module::from_bc_path(PANIC_BC_PATH)
+        .unwrap_or_else(|e| panic!("Failed to parse module: {}", e));
+    let analysis = ModuleAnalysis::new(&module);
+    let callgraph = analysis.call_graph();
+
+    let begin_panic = "_ZN3std9panicking11begin_panic17h5ae0871c3ba84f98E";
+    let payload_new =
+        "_ZN3std9panicking11begin_panic21PanicPayload$LT$A$GT$3new17h120501dac8746813E";
+    let start_name = Name::from("start");
+    let sites: Vec<&CallSite> = callgraph
+        .call_sites(begin_panic, payload_new)
+        .unwrap()
+        .collect();
+    assert_eq!(
+        sites,
+        vec![&CallSite {
+            caller: begin_panic,
+            block: &start_name,
+            index: 6,
+            inst_kind: CallInstKind::Invoke,
+            edge_kind: CallEdgeKind::Direct,
+        }]
+    );
+}